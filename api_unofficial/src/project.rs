@@ -1,6 +1,6 @@
 use std::fmt::Display;
 
-use codewars_types::{KataId, KnownLangId};
+use codewars_types::{KataId, LangId};
 use reqwest::header::AUTHORIZATION;
 use serde::{Deserialize, Serialize};
 
@@ -10,7 +10,7 @@ use crate::{jwt::find_jwt, Client};
 pub struct ProjectInfo {
     id: String,
     jwt: String,
-    lang: KnownLangId,
+    lang: LangId,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -40,7 +40,7 @@ impl Client {
     pub async fn start_project(
         &self,
         kata: &KataId,
-        lang: KnownLangId,
+        lang: &LangId,
     ) -> Result<ProjectInfo, StartProjectError> {
         let resp = self
             .client
@@ -60,7 +60,7 @@ impl Client {
             jwt: find_jwt(&resp)
                 .ok_or(StartProjectError::JwtNotFound)?
                 .to_string(),
-            lang,
+            lang: lang.clone(),
         };
         log::debug!("Start project {:?}", ret);
         Ok(ret)
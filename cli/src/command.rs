@@ -46,6 +46,7 @@ pub struct CmdEnv {
     pub root: String,
     pub index_path: PathBuf,
     pub workspace: String,
+    pub templates: Option<PathBuf>,
     pub runtime: tokio::runtime::Runtime,
     pub api_client: codewars_api::Client,
     pub unofficial_client: Option<codewars_unofficial::Client>,
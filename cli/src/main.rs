@@ -1,13 +1,16 @@
 extern crate codewars_api as api;
 extern crate codewars_solution as solution;
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use clap::{Parser, Subcommand};
-use std::path::Path;
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
 use tokio::runtime;
 
 use codewars_solution::index;
-use codewars_types::{KataId, KnownLangId};
+use codewars_types::{KataId, KnownLangId, LangId};
 
 mod command;
 use command::{next_cmd, print_err, CmdEnv, CmdState};
@@ -32,7 +35,7 @@ enum KataCmd {
     },
     Train {
         id: KataId,
-        lang: KnownLangId,
+        lang: LangId,
     },
     /// Suggest kata
     Suggest {
@@ -166,6 +169,33 @@ struct Cli {
     root: String,
     #[arg(long)]
     workspace: String,
+    /// directory of workspace templates, default to `$XDG_CONFIG_HOME/codewars/templates`
+    #[arg(long, env = "CW_TEMPLATES")]
+    templates: Option<PathBuf>,
+    /// wall clock timeout of build and clean commands in seconds
+    #[arg(long)]
+    build_timeout: Option<u64>,
+    /// wall clock timeout of local tests in seconds
+    #[arg(long, default_value_t = codewars_workspace::sandbox::CODEWARS_TIMEOUT)]
+    test_timeout: u64,
+    /// run clean commands and local tests of workspace in sandbox without network access
+    #[arg(long)]
+    sandbox: bool,
+    /// cpu time limit of sandboxed test processes in seconds
+    #[arg(long, requires = "sandbox")]
+    sandbox_cpu_time: Option<u64>,
+    /// wall clock limit of sandboxed test runs in seconds
+    #[arg(long, requires = "sandbox")]
+    sandbox_wall_time: Option<u64>,
+    /// memory limit of sandboxed processes in MiB
+    #[arg(long, requires = "sandbox")]
+    sandbox_memory: Option<u64>,
+    /// keep network access in sandbox
+    #[arg(long, requires = "sandbox")]
+    sandbox_network: bool,
+    /// additional writable directory in sandbox, e.g. build cache
+    #[arg(long, requires = "sandbox")]
+    sandbox_writable: Vec<PathBuf>,
     #[command(subcommand)]
     command: Option<Command>,
 }
@@ -174,6 +204,29 @@ fn main() -> Result<()> {
     env_logger::init();
 
     let cli = Cli::parse();
+    if codewars_workspace::process::set_timeouts(codewars_workspace::process::Timeouts {
+        build: cli.build_timeout.map(Duration::from_secs),
+        test: Some(Duration::from_secs(cli.test_timeout)),
+    })
+    .is_err()
+    {
+        bail!("failed to set command timeouts, they are already set");
+    }
+    if cli.sandbox {
+        let mut sandbox = codewars_workspace::sandbox::Sandbox::default();
+        if let Some(t) = cli.sandbox_cpu_time {
+            sandbox.cpu_time = Some(t);
+        }
+        if let Some(t) = cli.sandbox_wall_time {
+            sandbox.wall_time = Some(t);
+        }
+        sandbox.memory = cli.sandbox_memory.map(|m| m << 20);
+        sandbox.network = cli.sandbox_network;
+        sandbox.writable.extend(cli.sandbox_writable);
+        if codewars_workspace::sandbox::enable(sandbox).is_err() {
+            bail!("failed to enable sandbox, it is already configured");
+        }
+    }
     let env = {
         let runtime = runtime::Builder::new_current_thread()
             .enable_all()
@@ -183,6 +236,12 @@ fn main() -> Result<()> {
             index_path: Path::new(&cli.root).join(index::INDEX_FILE),
             root: cli.root,
             workspace: cli.workspace,
+            templates: cli.templates.or_else(|| {
+                std::env::var_os("XDG_CONFIG_HOME")
+                    .map(PathBuf::from)
+                    .or_else(|| std::env::var_os("HOME").map(|h| Path::new(&h).join(".config")))
+                    .map(|d| d.join("codewars/templates"))
+            }),
             api_client: codewars_api::Client::new(),
             unofficial_client: if cli.login {
                 println!("Login into codewars");
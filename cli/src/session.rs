@@ -8,7 +8,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use codewars_types::{KataId, KnownLangId};
+use codewars_types::{KataId, KnownLangId, LangId};
 use codewars_unofficial::project::{self, ProjectInfo, Session, SessionInfo};
 use codewars_workspace::{self as workspace, WorkspaceObject};
 
@@ -20,7 +20,7 @@ use crate::{
 #[derive(Debug, Serialize, Deserialize)]
 struct SessionState {
     kata_id: KataId,
-    language: KnownLangId,
+    language: LangId,
     slug: String,
     project: ProjectInfo,
     session: SessionInfo,
@@ -85,17 +85,17 @@ pub fn start_session(
     env: &CmdEnv,
     cmd_state: &mut CmdState,
     kata: KataId,
-    lang: KnownLangId,
+    lang: LangId,
 ) -> Result<()> {
     let client = env.unofficial_client.as_ref().context("login required")?;
     let project = env
         .runtime
-        .block_on(client.start_project(&kata, lang))
+        .block_on(client.start_project(&kata, &lang))
         .context("failed to start project")?;
     let theme = dialoguer::theme::ColorfulTheme::default();
     let ses_state = SessionState {
         kata_id: kata.clone(),
-        language: lang,
+        language: lang.clone(),
         slug: get_kata(env, cmd_state, &kata).context("failed to get kata")?,
         session: {
             let mut info = env
@@ -130,6 +130,27 @@ pub fn start_session(
         },
         project,
     };
+    let missing = match &lang {
+        LangId::Known(l) => workspace::probe::probe(*l, &ses_state.session.active_version),
+        LangId::Unknown(_) => Vec::new(),
+    }
+    .into_iter()
+    .filter(|r| !r.is_ok())
+    .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        println!("Toolchain problems:");
+        for r in missing.iter() {
+            println!("  {r}");
+        }
+        if !dialoguer::Confirm::with_theme(&theme)
+            .with_prompt("Create workspace anyway?")
+            .default(false)
+            .interact()
+            .context("failed to prompt toolchain problems")?
+        {
+            return Ok(());
+        }
+    }
     let workspace_root = create_workspace_dir(env, &ses_state, lang.as_str())
         .context("failed to create workspace dir")?;
     let workspace_cfg = workspace::Config {
@@ -144,6 +165,22 @@ pub fn start_session(
             .interact()
             .context("failed to prompt if there is preloaded code")?,
     };
+    if let Some(template) = match &env.templates {
+        Some(dir) => workspace::template::Template::load(dir, lang.as_str())
+            .context("failed to load workspace template")?,
+        None => None,
+    } {
+        return session_cmd(
+            env,
+            &ses_state,
+            &workspace_root,
+            &workspace::Custom::create(&workspace_root, workspace_cfg, template)
+                .context("failed to create workspace")?,
+        );
+    }
+    let LangId::Known(lang) = lang else {
+        bail!("Unsupported language {lang}")
+    };
     macro_rules! create {
         ($i:ident) => {
             session_cmd(
@@ -154,41 +191,64 @@ pub fn start_session(
                     .context("failed to create workspace")?,
             )
         };
+        // workspace taking toolchain of language version from catalog
+        ($i:ident, toolchain) => {{
+            let (workspace, substituted) = workspace::$i::create(&workspace_root, workspace_cfg)
+                .context("failed to create workspace")?;
+            if let Some(s) = substituted {
+                eprintln!("{}: {s}", yansi::Paint::yellow("warning"));
+            }
+            session_cmd(env, &ses_state, &workspace_root, &workspace)
+        }};
     }
     match lang {
         KnownLangId::Coq => create!(Coq),
-        KnownLangId::Rust => create!(Rust),
-        KnownLangId::Haskell => create!(Haskell),
-        KnownLangId::Java => create!(Java),
-        KnownLangId::Kotlin => session_cmd(
-            env,
-            &ses_state,
-            &workspace_root,
-            &workspace::Kotlin::create(
-                &workspace_root,
-                workspace_cfg,
-                dialoguer::Input::with_theme(&theme)
-                    .with_prompt("patch version?")
-                    .interact()
-                    .context("failed to get kotlin compiler patch version")?,
-            )
-            .context("failed to create workspace")?,
-        ),
-        KnownLangId::TypeScript => create!(TypeScript),
-        KnownLangId::Scala => session_cmd(
-            env,
-            &ses_state,
-            &workspace_root,
-            &workspace::Scala::create(
-                &workspace_root,
-                workspace_cfg,
-                &dialoguer::Input::<String>::with_theme(&theme)
-                    .with_prompt("scala version?")
-                    .interact()
-                    .context("failed to get scala version")?,
-            )
-            .context("failed to create workspace")?,
-        ),
+        KnownLangId::Rust => {
+            let (workspace, substituted, unavailable) =
+                workspace::Rust::create(&workspace_root, workspace_cfg)
+                    .context("failed to create workspace")?;
+            if let Some(s) = substituted {
+                eprintln!("{}: {s}", yansi::Paint::yellow("warning"));
+            }
+            for c in unavailable {
+                eprintln!("{}: {c}", yansi::Paint::yellow("warning"));
+            }
+            session_cmd(env, &ses_state, &workspace_root, &workspace)
+        }
+        KnownLangId::Haskell => create!(Haskell, toolchain),
+        KnownLangId::Java => create!(Java, toolchain),
+        KnownLangId::Kotlin => create!(Kotlin, toolchain),
+        KnownLangId::TypeScript => create!(TypeScript, toolchain),
+        KnownLangId::Lean => create!(Lean, toolchain),
+        KnownLangId::Agda => create!(Agda),
+        KnownLangId::Idris => create!(Idris),
+        KnownLangId::CSharp => create!(CSharp, toolchain),
+        KnownLangId::FSharp => create!(FSharp, toolchain),
+        KnownLangId::Elixir => create!(Elixir, toolchain),
+        KnownLangId::Erlang => create!(Erlang, toolchain),
+        KnownLangId::Ruby => create!(Ruby, toolchain),
+        KnownLangId::Nasm => create!(Nasm),
+        KnownLangId::RiscV => create!(RiscV),
+        KnownLangId::Clojure => create!(Clojure, toolchain),
+        KnownLangId::Racket => create!(Racket, toolchain),
+        KnownLangId::PureScript => create!(PureScript, toolchain),
+        KnownLangId::Elm => create!(Elm, toolchain),
+        KnownLangId::Sql => create!(Sql),
+        KnownLangId::Shell => create!(Shell),
+        KnownLangId::Swift => create!(Swift, toolchain),
+        KnownLangId::Dart => create!(Dart, toolchain),
+        KnownLangId::Julia => create!(Julia, toolchain),
+        KnownLangId::R => create!(R),
+        KnownLangId::Lua => create!(Lua, toolchain),
+        KnownLangId::Nim => create!(Nim, toolchain),
+        KnownLangId::Crystal => create!(Crystal, toolchain),
+        KnownLangId::D => create!(D, toolchain),
+        KnownLangId::Prolog => create!(Prolog),
+        KnownLangId::CommonLisp => create!(CommonLisp),
+        KnownLangId::Fortran => create!(Fortran),
+        KnownLangId::BrainFuck => create!(BrainFuck),
+        KnownLangId::LambdaCalc => create!(LambdaCalc),
+        KnownLangId::Scala => create!(Scala, toolchain),
         l => {
             bail!("Unsupported language {l}")
         }
@@ -211,7 +271,13 @@ pub fn open_session(env: &CmdEnv, _: &mut CmdState, path: impl AsRef<Path>) -> R
             )
         };
     }
-    match state.language {
+    if workspace::Custom::is_template(workspace_root) {
+        return open!(Custom);
+    }
+    let LangId::Known(lang) = &state.language else {
+        bail!("Unsupported language {}", state.language)
+    };
+    match lang {
         KnownLangId::Coq => open!(Coq),
         KnownLangId::Rust => open!(Rust),
         KnownLangId::Haskell => open!(Haskell),
@@ -219,6 +285,35 @@ pub fn open_session(env: &CmdEnv, _: &mut CmdState, path: impl AsRef<Path>) -> R
         KnownLangId::Kotlin => open!(Kotlin),
         KnownLangId::TypeScript => open!(TypeScript),
         KnownLangId::Scala => open!(Scala),
+        KnownLangId::Lean => open!(Lean),
+        KnownLangId::Agda => open!(Agda),
+        KnownLangId::Idris => open!(Idris),
+        KnownLangId::CSharp => open!(CSharp),
+        KnownLangId::FSharp => open!(FSharp),
+        KnownLangId::Elixir => open!(Elixir),
+        KnownLangId::Erlang => open!(Erlang),
+        KnownLangId::Ruby => open!(Ruby),
+        KnownLangId::Nasm => open!(Nasm),
+        KnownLangId::RiscV => open!(RiscV),
+        KnownLangId::Clojure => open!(Clojure),
+        KnownLangId::Racket => open!(Racket),
+        KnownLangId::PureScript => open!(PureScript),
+        KnownLangId::Elm => open!(Elm),
+        KnownLangId::Sql => open!(Sql),
+        KnownLangId::Shell => open!(Shell),
+        KnownLangId::Swift => open!(Swift),
+        KnownLangId::Dart => open!(Dart),
+        KnownLangId::Julia => open!(Julia),
+        KnownLangId::R => open!(R),
+        KnownLangId::Lua => open!(Lua),
+        KnownLangId::Nim => open!(Nim),
+        KnownLangId::Crystal => open!(Crystal),
+        KnownLangId::D => open!(D),
+        KnownLangId::Prolog => open!(Prolog),
+        KnownLangId::CommonLisp => open!(CommonLisp),
+        KnownLangId::Fortran => open!(Fortran),
+        KnownLangId::BrainFuck => open!(BrainFuck),
+        KnownLangId::LambdaCalc => open!(LambdaCalc),
         l => {
             bail!("Unsupported language {l}")
         }
@@ -249,8 +344,15 @@ enum SessionCmd {
     Show,
     /// run sample test
     Test,
+    /// run sample test locally
+    Local,
     Attempt,
     Submit,
+    /// run solution with local interpreter
+    Eval {
+        /// program input or expression to evaluate
+        input: String,
+    },
     Clean {
         #[command(subcommand)]
         cmd: CleanCmd,
@@ -360,6 +462,14 @@ fn session_cmd(
                     Err(e) => print_err(e),
                 }
             }
+            SessionCmd::Local => match workspace.local_test() {
+                Ok(true) => (),
+                Ok(false) => println!(
+                    "Local test is not supported for {}",
+                    ses_state.session.language_name
+                ),
+                Err(e) => print_err(anyhow::Error::new(e).context("failed to run local test")),
+            },
             SessionCmd::Attempt => {
                 match workspace
                     .get_code()
@@ -378,6 +488,14 @@ fn session_cmd(
                     print_err(anyhow::Error::new(e))
                 }
             }
+            SessionCmd::Eval { input } => match workspace.eval(&input) {
+                Ok(Some(out)) => println!("{out}"),
+                Ok(None) => println!(
+                    "Local evaluation is not supported for {}",
+                    ses_state.session.language_name
+                ),
+                Err(e) => print_err(anyhow::Error::new(e).context("failed to evaluate")),
+            },
             SessionCmd::Clean { cmd } => {
                 if let Err(e) = clean(cmd, workspace_root, workspace) {
                     print_err(e)
@@ -135,7 +135,8 @@ fn suggest_cmd(
             }
             SuggestCmd::Show => show_suggestion(&current),
             SuggestCmd::Train => {
-                if let Err(e) = session::start_session(env, state, current.id.clone(), lang) {
+                if let Err(e) = session::start_session(env, state, current.id.clone(), lang.into())
+                {
                     print_err(e)
                 }
             }
@@ -0,0 +1,298 @@
+//! Local toolchains matching language versions of Codewars runners
+
+use crate::KnownLangId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Toolchain {
+    pub lang: KnownLangId,
+    /// `LangVersion.id` used by Codewars
+    pub version_id: &'static str,
+    /// toolchain version used locally
+    pub version: &'static str,
+    /// test framework identifier, e.g. maven coordinate or test package
+    pub test_framework: &'static str,
+    /// `(name, version)` of packages provided by Codewars runner
+    pub packages: &'static [(&'static str, &'static str)],
+    /// `(name, version)` of packages only available to tests
+    pub test_packages: &'static [(&'static str, &'static str)],
+    /// language specific `(key, value)` settings, e.g. compiler options or .NET target framework
+    pub options: &'static [(&'static str, &'static str)],
+}
+impl Toolchain {
+    /// value of language specific setting `key`
+    pub fn option(&self, key: &str) -> Option<&'static str> {
+        self.options
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| *v)
+    }
+}
+
+macro_rules! catalog {
+    ($($lang:ident $id:literal => $version:literal, $test:literal
+        $({ $($field:ident: [$(($name:literal, $value:literal)),* $(,)?]),* $(,)? })?;)+) => {
+        const CATALOG: &[Toolchain] = &[
+            $({
+                #[allow(unused_mut)]
+                let mut t = Toolchain {
+                    lang: KnownLangId::$lang,
+                    version_id: $id,
+                    version: $version,
+                    test_framework: $test,
+                    packages: &[],
+                    test_packages: &[],
+                    options: &[],
+                };
+                $($(t.$field = &[$(($name, $value)),*];)*)?
+                t
+            },)+
+        ];
+    };
+}
+
+// rows of each language are listed from oldest to newest version
+catalog! {
+    Agda "2.6" => "2.6.4.3", "agda";
+    Clojure "1.10" => "1.10.3", "clojure.test";
+    Clojure "1.11" => "1.11.1", "clojure.test";
+    Coq "8.16" => "8.16.1", "coq";
+    Coq "8.18" => "8.18.0", "coq";
+    Crystal "1.10" => "1.10.1", "spec";
+    CSharp "8.0" => "3.1.426", "nunit" {
+        options: [("target_framework", "netcoreapp3.1")],
+    };
+    CSharp "9.0" => "5.0.408", "nunit" {
+        options: [("target_framework", "net5.0")],
+    };
+    CSharp "10.0" => "6.0.428", "nunit" {
+        options: [("target_framework", "net6.0")],
+    };
+    CSharp "12.0" => "8.0.404", "nunit" {
+        options: [("target_framework", "net8.0")],
+    };
+    D "2.098" => "2.098.1", "unit-threaded";
+    D "2.106" => "2.106.1", "unit-threaded";
+    Dart "2.19" => "2.19.6", "test";
+    Dart "3.3" => "3.3.4", "test";
+    Elm "0.19" => "0.19.1", "elm-explorations/test";
+    Elixir "1.15" => "1.15.7", "ExUnit";
+    Erlang "25" => "25.3", "eunit";
+    Erlang "26" => "26.2", "eunit";
+    FSharp "5.0" => "5.0.408", "nunit" {
+        options: [("target_framework", "net5.0")],
+    };
+    FSharp "6.0" => "6.0.428", "nunit" {
+        options: [("target_framework", "net6.0")],
+    };
+    FSharp "8.0" => "8.0.404", "nunit" {
+        options: [("target_framework", "net8.0")],
+    };
+    Haskell "8.10" => "8.10.7", "hspec";
+    Haskell "9.2" => "9.2.8", "hspec";
+    Idris "1.3" => "1.3.4", "specdris";
+    Java "11" => "11", "org.junit.jupiter:junit-jupiter:5.8.2";
+    Java "17" => "17", "org.junit.jupiter:junit-jupiter:5.8.2";
+    Java "21" => "21", "org.junit.jupiter:junit-jupiter:5.10.2";
+    Julia "1.9" => "1.9.4", "FactCheck";
+    Julia "1.10" => "1.10.4", "FactCheck";
+    Kotlin "1.3" => "1.3.72", "kotlin-test";
+    Kotlin "1.4" => "1.4.32", "kotlin-test";
+    Kotlin "1.5" => "1.5.32", "kotlin-test";
+    Kotlin "1.6" => "1.6.21", "kotlin-test";
+    Kotlin "1.7" => "1.7.22", "kotlin-test";
+    Kotlin "1.8" => "1.8.22", "kotlin-test";
+    Kotlin "1.9" => "1.9.25", "kotlin-test";
+    Lean "4.9" => "4.9.0", "lean";
+    Lua "5.3" => "5.3.6", "busted" {
+        options: [("interpreter", "lua5.3")],
+    };
+    Lua "5.4" => "5.4.6", "busted" {
+        options: [("interpreter", "lua5.4")],
+    };
+    Nim "1.6" => "1.6.14", "unittest";
+    Nim "2.0" => "2.0.2", "unittest";
+    PureScript "0.15" => "0.15.15", "spec" {
+        options: [("registry", "60.0.0")],
+    };
+    R "4.3" => "4.3.3", "testthat";
+    Racket "8.6" => "8.6", "rackunit";
+    Ruby "2.5" => "2.5.9", "rspec";
+    Ruby "3.0" => "3.0.6", "rspec";
+    Ruby "3.1" => "3.1.4", "rspec";
+    Rust "1.62" => "1.62", "rust" {
+        packages: [
+            ("bit-set", "0.5.2"),
+            ("chrono", "0.4.19"),
+            ("either", "1.7.0"),
+            ("fancy-regex", "0.10.0"),
+            ("futures", "0.3.21"),
+            ("im", "15.1.0"),
+            ("itertools", "0.10.3"),
+            ("lazy_static", "1.4.0"),
+            ("num", "0.4.0"),
+            ("once_cell", "1.13.0"),
+            ("rand", "0.8.5"),
+            ("regex", "1.6.0"),
+            ("serde", "1.0.139"),
+            ("serde_json", "1.0.82"),
+            ("text_io", "0.1.10"),
+            ("thiserror", "1.0.31"),
+            ("tokio", "1.20.0"),
+            ("tokio-util", "0.7.3"),
+        ],
+        test_packages: [
+            ("quickcheck", "1.0.3"),
+            ("quickcheck_macros", "1.0.0"),
+            ("float_eq", "1.0.0"),
+        ],
+    };
+    Rust "1.66" => "1.66", "rust" {
+        packages: [
+            ("bit-set", "0.5.3"),
+            ("chrono", "0.4.23"),
+            ("either", "1.8.0"),
+            ("fancy-regex", "0.11.0"),
+            ("futures", "0.3.25"),
+            ("im", "15.1.0"),
+            ("itertools", "0.10.5"),
+            ("lazy_static", "1.4.0"),
+            ("num", "0.4.0"),
+            ("once_cell", "1.17.0"),
+            ("rand", "0.8.5"),
+            ("regex", "1.7.1"),
+            ("serde", "1.0.152"),
+            ("serde_json", "1.0.91"),
+            ("text_io", "0.1.12"),
+            ("thiserror", "1.0.38"),
+            ("tokio", "1.24.2"),
+            ("tokio-util", "0.7.4"),
+        ],
+        test_packages: [
+            ("quickcheck", "1.0.3"),
+            ("quickcheck_macros", "1.0.0"),
+            ("float_eq", "1.0.1"),
+        ],
+    };
+    Rust "1.75" => "1.75", "rust" {
+        packages: [
+            ("bit-set", "0.5.3"),
+            ("chrono", "0.4.31"),
+            ("either", "1.9.0"),
+            ("fancy-regex", "0.12.0"),
+            ("futures", "0.3.29"),
+            ("im", "15.1.0"),
+            ("itertools", "0.12.0"),
+            ("lazy_static", "1.4.0"),
+            ("num", "0.4.1"),
+            ("once_cell", "1.19.0"),
+            ("rand", "0.8.5"),
+            ("regex", "1.10.2"),
+            ("serde", "1.0.193"),
+            ("serde_json", "1.0.108"),
+            ("text_io", "0.1.12"),
+            ("thiserror", "1.0.50"),
+            ("tokio", "1.35.0"),
+            ("tokio-util", "0.7.10"),
+        ],
+        test_packages: [
+            ("quickcheck", "1.0.3"),
+            ("quickcheck_macros", "1.0.0"),
+            ("float_eq", "1.0.1"),
+        ],
+    };
+    Scala "2.13" => "2.13.14", "org.scalatest::scalatest:3.0.8";
+    Scala "3.0" => "3.0.2", "org.scalatest::scalatest:3.2.10";
+    Swift "5.5" => "5.5.3", "XCTest";
+    Swift "5.9" => "5.9.2", "XCTest";
+    TypeScript "3.3" => "3.3.4000", "mocha" {
+        test_packages: [
+            ("mocha", "5.2.0"),
+            ("@types/mocha", "5.2.7"),
+            ("chai", "4.2.0"),
+            ("@types/chai", "4.1.7"),
+        ],
+        options: [("target", "ES2017"), ("module", "CommonJS")],
+    };
+    TypeScript "4.2" => "4.2.4", "mocha" {
+        test_packages: [
+            ("mocha", "8.3.2"),
+            ("@types/mocha", "8.2.2"),
+            ("chai", "4.3.4"),
+            ("@types/chai", "4.2.16"),
+        ],
+        options: [("target", "ES2019"), ("module", "CommonJS")],
+    };
+    TypeScript "4.9" => "4.9.5", "mocha" {
+        test_packages: [
+            ("mocha", "10.2.0"),
+            ("@types/mocha", "10.0.1"),
+            ("chai", "4.3.7"),
+            ("@types/chai", "4.3.4"),
+        ],
+        options: [("target", "ES2022"), ("module", "CommonJS")],
+    };
+}
+
+/// Toolchain of Codewars language version
+pub fn toolchain(lang: KnownLangId, version_id: &str) -> Option<&'static Toolchain> {
+    CATALOG
+        .iter()
+        .find(|t| t.lang == lang && t.version_id == version_id)
+}
+
+/// All known toolchains of `lang`, oldest first
+pub fn toolchains(lang: KnownLangId) -> impl Iterator<Item = &'static Toolchain> {
+    CATALOG.iter().filter(move |t| t.lang == lang)
+}
+
+/// Newest known toolchain of `lang`
+pub fn latest(lang: KnownLangId) -> Option<&'static Toolchain> {
+    toolchains(lang).last()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{latest, toolchain, toolchains};
+    use crate::KnownLangId;
+
+    #[test]
+    fn lookup() {
+        assert_eq!(
+            toolchain(KnownLangId::Kotlin, "1.9").map(|t| t.version),
+            Some("1.9.25")
+        );
+        assert_eq!(toolchain(KnownLangId::Kotlin, "2.13"), None);
+        assert!(toolchains(KnownLangId::Scala).all(|t| t.lang == KnownLangId::Scala));
+        assert_eq!(
+            latest(KnownLangId::Kotlin).map(|t| t.version_id),
+            Some("1.9")
+        );
+        assert_eq!(latest(KnownLangId::Sql), None);
+    }
+
+    #[test]
+    fn packages() {
+        let rust = toolchain(KnownLangId::Rust, "1.75").unwrap();
+        assert!(rust.packages.contains(&("itertools", "0.12.0")));
+        assert!(rust.test_packages.iter().any(|(n, _)| *n == "quickcheck"));
+        assert!(toolchain(KnownLangId::Kotlin, "1.9")
+            .unwrap()
+            .packages
+            .is_empty());
+        assert_eq!(
+            toolchain(KnownLangId::TypeScript, "4.9").and_then(|t| t.option("target")),
+            Some("ES2022")
+        );
+        assert_eq!(
+            toolchain(KnownLangId::CSharp, "8.0").and_then(|t| t.option("target_framework")),
+            Some("netcoreapp3.1")
+        );
+        assert!(toolchains(KnownLangId::CSharp)
+            .chain(toolchains(KnownLangId::FSharp))
+            .all(|t| t.option("target_framework").is_some()));
+        assert_eq!(
+            toolchain(KnownLangId::Lua, "5.4").and_then(|t| t.option("interpreter")),
+            Some("lua5.4")
+        );
+    }
+}
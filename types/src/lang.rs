@@ -125,6 +125,25 @@ impl LangId {
         }
     }
 }
+impl Display for LangId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+impl From<KnownLangId> for LangId {
+    fn from(value: KnownLangId) -> Self {
+        Self::Known(value)
+    }
+}
+impl FromStr for LangId {
+    type Err = std::convert::Infallible;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match KnownLangId::from_lang_id(s) {
+            Some(l) => Self::Known(l),
+            None => Self::Unknown(s.to_owned()),
+        })
+    }
+}
 impl Serialize for LangId {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -19,3 +19,5 @@ pub use rank::{KataRankId, UserRankId};
 
 pub mod kata_id;
 pub use kata_id::KataId;
+
+pub mod catalog;
@@ -0,0 +1,134 @@
+use std::{
+    ffi::{CStr, CString},
+    io,
+    path::Path,
+};
+
+use rustix::{
+    fd::{AsFd, OwnedFd},
+    io::Errno,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    haskell::parse_header,
+    util::{fs, write_module},
+    Code, Config, WorkspaceObject,
+};
+
+const STATE_FILE: &CStr = c"agda_state.json";
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    code_path: CString,
+    fixture_path: CString,
+}
+
+#[derive(Debug, thiserror::Error)]
+enum OpenErrInner {
+    #[error("failed to open state")]
+    Io(#[source] Errno),
+    #[error("failed to deserialize json")]
+    Json(#[source] serde_json::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct OpenError(#[from] OpenErrInner);
+
+#[derive(Debug, thiserror::Error)]
+enum CreateErrorInner {
+    #[error("unknown code {0}")]
+    UnknownCode(String),
+    #[error("unknown test {0}")]
+    UnknownTest(String),
+    #[error("failed to write source code")]
+    WriteCode(#[source] io::Error),
+    #[error("io error")]
+    Io(#[source] Errno),
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct CreateError(#[from] CreateErrorInner);
+
+pub struct Agda {
+    root: OwnedFd,
+    state: State,
+}
+
+impl Agda {
+    pub fn open(root: &Path) -> Result<Self, OpenError> {
+        let root = fs::open_dirfd(root).map_err(OpenErrInner::Io)?;
+        Ok(Self {
+            state: serde_json::from_slice(
+                &fs::read(root.as_fd(), STATE_FILE).map_err(OpenErrInner::Io)?,
+            )
+            .map_err(OpenErrInner::Json)?,
+            root,
+        })
+    }
+    pub fn create(root: &Path, project: Config) -> Result<Self, CreateError> {
+        let root = fs::open_dirfd(root).map_err(CreateErrorInner::Io)?;
+
+        let code_mod = parse_header(project.code)
+            .module
+            .ok_or_else(|| CreateErrorInner::UnknownCode(project.code.to_string()))?;
+        let code_path = write_module(root.as_fd(), "src", &code_mod, "agda", project.code)
+            .map_err(CreateErrorInner::WriteCode)?;
+
+        let test_mod = parse_header(project.fixture)
+            .module
+            .ok_or_else(|| CreateErrorInner::UnknownTest(project.fixture.to_string()))?;
+        let fixture_path = write_module(root.as_fd(), "test", &test_mod, "agda", project.fixture)
+            .map_err(CreateErrorInner::WriteCode)?;
+
+        if project.has_preload {
+            fs::write(
+                root.as_fd(),
+                c"src/Preloaded.agda",
+                "module Preloaded where\n",
+            )
+            .map_err(CreateErrorInner::Io)?;
+        }
+
+        fs::write(
+            root.as_fd(),
+            format!("{}.agda-lib", project.slug),
+            format!(
+                include_str!("./agda/challenge.agda-lib"),
+                package = project.slug
+            ),
+        )
+        .map_err(CreateErrorInner::Io)?;
+
+        let state = State {
+            code_path,
+            fixture_path,
+        };
+        fs::write(
+            root.as_fd(),
+            STATE_FILE,
+            serde_json::to_vec(&state).unwrap(),
+        )
+        .map_err(CreateErrorInner::Io)?;
+
+        Ok(Self { root, state })
+    }
+}
+impl WorkspaceObject for Agda {
+    fn get_code(&self) -> Result<Code, io::Error> {
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), &self.state.code_path)?,
+            fixture: fs::read_to_string(self.root.as_fd(), &self.state.fixture_path)?,
+        })
+    }
+    fn clean_build(&self) -> Result<(), io::Error> {
+        fs::remove_dir_all_at(self.root.as_fd(), c"_build")?;
+        Ok(())
+    }
+    fn clean_session(&self) -> Result<(), io::Error> {
+        fs::remove_at(self.root.as_fd(), STATE_FILE)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use std::{ffi::CStr, io, path::Path};
+
+use rustix::fd::{AsFd, OwnedFd};
+
+use crate::{
+    util::{build_timeout, call_command_at, fs},
+    Code, Config,
+};
+
+const FIXTURE_FILE: &CStr = c"fixture.c";
+const PRELOADED_FILE: &CStr = c"preloaded.c";
+
+/// Assembler and runner of an assembly language, described by its Makefile
+pub(crate) struct Toolchain {
+    /// file name of solution code
+    pub code_file: &'static CStr,
+    /// Makefile building solution with C criterion fixture, `make test` runs it
+    pub makefile: &'static str,
+}
+
+/// assembly solution linked with C fixture and optional C preloaded code
+pub(crate) struct Project {
+    root: OwnedFd,
+    toolchain: &'static Toolchain,
+}
+impl Project {
+    pub fn create(root: &Path, project: Config, toolchain: &'static Toolchain) -> io::Result<Self> {
+        let root = fs::open_dirfd(root)?;
+
+        fs::write(root.as_fd(), toolchain.code_file, project.code)?;
+        fs::write(root.as_fd(), FIXTURE_FILE, project.fixture)?;
+        if project.has_preload {
+            fs::write(root.as_fd(), PRELOADED_FILE, "")?;
+        }
+        fs::write(root.as_fd(), c"Makefile", toolchain.makefile)?;
+
+        Ok(Self { root, toolchain })
+    }
+    pub fn open(root: &Path, toolchain: &'static Toolchain) -> io::Result<Self> {
+        Ok(Self {
+            root: fs::open_dirfd(root)?,
+            toolchain,
+        })
+    }
+    pub fn get_code(&self) -> Result<Code, io::Error> {
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), self.toolchain.code_file)?,
+            fixture: fs::read_to_string(self.root.as_fd(), FIXTURE_FILE)?,
+        })
+    }
+    pub fn clean_build(&self) -> Result<(), io::Error> {
+        call_command_at(self.root.as_fd(), "make", ["clean"], build_timeout())?.check()?;
+        Ok(())
+    }
+}
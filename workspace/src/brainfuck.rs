@@ -0,0 +1,161 @@
+use std::{ffi::CStr, io, path::Path};
+
+use rustix::fd::{AsFd, OwnedFd};
+
+use crate::{
+    interp::{bf, cases},
+    util::fs,
+    Code, Config, WorkspaceObject,
+};
+
+const CODE_FILE: &CStr = c"solution.b";
+const FIXTURE_FILE: &CStr = c"test.js";
+const CONFIG_FILE: &CStr = c"interp.json";
+
+pub struct BrainFuck {
+    root: OwnedFd,
+}
+/// Bytes of string like on Codewars, where each char is one byte of its char code
+///
+/// `None` if some char is above 255.
+fn bytes(s: &str) -> Option<Vec<u8>> {
+    s.chars().map(|c| u8::try_from(c as u32).ok()).collect()
+}
+
+/// String of bytes with one char per byte, inverse of [`bytes`]
+fn latin1(bytes: &[u8]) -> String {
+    bytes.iter().map(|&b| char::from(b)).collect()
+}
+
+/// Input and expected output of `assert.<method>(run(input), expected)` in fixture
+///
+/// Input is the last argument of the call, the solution is passed implicitly.
+/// Strings with chars above 255 can not be used by BF, their assertions are skipped.
+fn test_cases(fixture: &str) -> cases::Cases<(String, Vec<u8>, String)> {
+    cases::extract(fixture, |a| {
+        let (_, calls) = cases::call(a.args.first()?)?;
+        let input = match calls.as_slice() {
+            [args] => args
+                .last()
+                .map_or(Some(String::new()), |a| cases::string(a))?,
+            _ => return None,
+        };
+        let expected = cases::string(a.args.get(1)?)?;
+        bytes(&expected)?;
+        Some((a.args[0].to_string(), bytes(&input)?, expected))
+    })
+}
+
+impl BrainFuck {
+    pub fn create(root: &Path, project: Config) -> io::Result<Self> {
+        let root = fs::open_dirfd(root)?;
+
+        fs::write(root.as_fd(), CODE_FILE, project.code)?;
+        fs::write(root.as_fd(), FIXTURE_FILE, project.fixture)?;
+        fs::write(
+            root.as_fd(),
+            CONFIG_FILE,
+            serde_json::to_string_pretty(&bf::Config::default()).unwrap(),
+        )?;
+
+        Ok(Self { root })
+    }
+    pub fn open(root: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self {
+            root: fs::open_dirfd(root.as_ref())?,
+        })
+    }
+}
+impl WorkspaceObject for BrainFuck {
+    fn get_code(&self) -> Result<Code, io::Error> {
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), CODE_FILE)?,
+            fixture: fs::read_to_string(self.root.as_fd(), FIXTURE_FILE)?,
+        })
+    }
+    fn clean_build(&self) -> Result<(), io::Error> {
+        Ok(())
+    }
+    fn clean_session(&self) -> Result<(), io::Error> {
+        Ok(())
+    }
+    fn eval(&self, input: &str) -> Result<Option<String>, io::Error> {
+        let config: bf::Config =
+            serde_json::from_str(&fs::read_to_string(self.root.as_fd(), CONFIG_FILE)?)?;
+        let code = fs::read_to_string(self.root.as_fd(), CODE_FILE)?;
+        let input = bytes(input)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "input char above 255"))?;
+        let output = bf::run(&code, &input, &config).map_err(io::Error::other)?;
+        Ok(Some(latin1(&output)))
+    }
+    fn local_test(&self) -> Result<bool, io::Error> {
+        let config: bf::Config =
+            serde_json::from_str(&fs::read_to_string(self.root.as_fd(), CONFIG_FILE)?)?;
+        let code = fs::read_to_string(self.root.as_fd(), CODE_FILE)?;
+        let fixture = fs::read_to_string(self.root.as_fd(), FIXTURE_FILE)?;
+        let cases = test_cases(&fixture);
+        let outcomes: Vec<_> = cases
+            .cases
+            .into_iter()
+            .map(|(case, input, expected)| {
+                let failure = match bf::run(&code, &input, &config).map(|out| latin1(&out)) {
+                    Ok(out) if out == expected => None,
+                    Ok(out) => Some(format!("expected {expected:?}, got {out:?}")),
+                    Err(e) => Some(e.to_string()),
+                };
+                (case, failure)
+            })
+            .collect();
+        cases::report(&outcomes, &cases.skipped)?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{bytes, latin1, test_cases};
+
+    #[test]
+    fn fixture_cases() {
+        let fixture = r#"describe("echo", () => {
+  it("copies input", () => {
+    assert.strictEqual(runBF("abc"), "abc");
+    assert.strictEqual(runBF(), "", "empty input");
+    assert.strictEqual(runBF(input), "x");
+    assert.strictEqual(runBF("\xff"), "\u00e9");
+    assert.strictEqual(runBF("\u0100"), "");
+  });
+});"#;
+        let cases = test_cases(fixture);
+        assert_eq!(
+            cases.cases,
+            [
+                (
+                    r#"runBF("abc")"#.to_string(),
+                    b"abc".to_vec(),
+                    "abc".to_string()
+                ),
+                ("runBF()".to_string(), Vec::new(), String::new()),
+                (
+                    r#"runBF("\xff")"#.to_string(),
+                    vec![0xff],
+                    "\u{e9}".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            cases.skipped,
+            [
+                r#"assert.strictEqual(runBF(input), "x")"#,
+                r#"assert.strictEqual(runBF("\u0100"), "")"#
+            ]
+        );
+    }
+
+    #[test]
+    fn byte_strings() {
+        assert_eq!(bytes("a\u{ff}"), Some(vec![b'a', 0xff]));
+        assert_eq!(bytes("\u{100}"), None);
+        assert_eq!(latin1(&[b'a', 0xff]), "a\u{ff}");
+    }
+}
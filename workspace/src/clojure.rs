@@ -0,0 +1,199 @@
+use std::{
+    ffi::{CStr, CString},
+    io,
+    path::Path,
+};
+
+use codewars_types::KnownLangId;
+use rustix::{
+    fd::{AsFd, OwnedFd},
+    io::Errno,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    util::{fs, toolchain, write_module},
+    Code, Config, SubstitutedToolchain, WorkspaceObject,
+};
+
+const STATE_FILE: &CStr = c"clojure_state.json";
+const PRELOADED_PATH: &CStr = c"src/preloaded.clj";
+
+/// get namespace declared by the first `ns` form
+fn namespace(src: &str) -> Option<&str> {
+    let mut s = src.trim_start();
+    while s.starts_with(';') {
+        s = s.split_once('\n')?.1.trim_start();
+    }
+    let mut s = s.strip_prefix('(')?.trim_start().strip_prefix("ns")?;
+    if !s.starts_with(char::is_whitespace) {
+        return None;
+    }
+    s = s.trim_start();
+    // skip metadata like `^:no-doc`
+    while let Some(t) = s.strip_prefix('^') {
+        s = t.split_once(char::is_whitespace)?.1.trim_start();
+    }
+    let name = &s[..s
+        .find(|c: char| c.is_whitespace() || c == ')' || c == '(')
+        .unwrap_or(s.len())];
+    if name.is_empty() {
+        None
+    } else {
+        Some(name)
+    }
+}
+
+/// clojure maps `-` in namespace to `_` in file name
+fn namespace_file(ns: &str) -> String {
+    ns.replace('-', "_")
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    code_path: CString,
+    fixture_path: CString,
+}
+
+#[derive(Debug, thiserror::Error)]
+enum OpenErrInner {
+    #[error("failed to open state")]
+    Io(#[source] Errno),
+    #[error("failed to deserialize json")]
+    Json(#[source] serde_json::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct OpenError(#[from] OpenErrInner);
+
+#[derive(Debug, thiserror::Error)]
+enum CreateErrorInner {
+    #[error("unknown code {0}")]
+    UnknownCode(String),
+    #[error("unknown test {0}")]
+    UnknownTest(String),
+    #[error("failed to write source code")]
+    WriteCode(#[source] io::Error),
+    #[error("io error")]
+    Io(#[source] Errno),
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct CreateError(#[from] CreateErrorInner);
+
+pub struct Clojure {
+    root: OwnedFd,
+    state: State,
+}
+
+impl Clojure {
+    pub fn open(root: &Path) -> Result<Self, OpenError> {
+        let root = fs::open_dirfd(root).map_err(OpenErrInner::Io)?;
+        Ok(Self {
+            state: serde_json::from_slice(
+                &fs::read(root.as_fd(), STATE_FILE).map_err(OpenErrInner::Io)?,
+            )
+            .map_err(OpenErrInner::Json)?,
+            root,
+        })
+    }
+    pub fn create(
+        root: &Path,
+        project: Config,
+    ) -> Result<(Self, Option<SubstitutedToolchain>), CreateError> {
+        let (toolchain, substituted) = toolchain(KnownLangId::Clojure, project.version_id);
+        let root = fs::open_dirfd(root).map_err(CreateErrorInner::Io)?;
+
+        let code_ns = namespace(project.code)
+            .ok_or_else(|| CreateErrorInner::UnknownCode(project.code.to_string()))?;
+        let code_path = write_module(
+            root.as_fd(),
+            "src",
+            &namespace_file(code_ns),
+            "clj",
+            project.code,
+        )
+        .map_err(CreateErrorInner::WriteCode)?;
+
+        if project.has_preload {
+            fs::write(root.as_fd(), PRELOADED_PATH, "(ns preloaded)\n")
+                .map_err(CreateErrorInner::Io)?;
+        }
+
+        let test_ns = namespace(project.fixture)
+            .ok_or_else(|| CreateErrorInner::UnknownTest(project.fixture.to_string()))?;
+        let fixture_path = write_module(
+            root.as_fd(),
+            "test",
+            &namespace_file(test_ns),
+            "clj",
+            project.fixture,
+        )
+        .map_err(CreateErrorInner::WriteCode)?;
+
+        fs::write(
+            root.as_fd(),
+            c"deps.edn",
+            format!(
+                include_str!("./clojure/deps.edn"),
+                clojure_version = toolchain.version
+            ),
+        )
+        .map_err(CreateErrorInner::Io)?;
+
+        let state = State {
+            code_path,
+            fixture_path,
+        };
+        fs::write(
+            root.as_fd(),
+            STATE_FILE,
+            serde_json::to_vec(&state).unwrap(),
+        )
+        .map_err(CreateErrorInner::Io)?;
+
+        Ok((Self { root, state }, substituted))
+    }
+}
+impl WorkspaceObject for Clojure {
+    fn get_code(&self) -> Result<Code, io::Error> {
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), &self.state.code_path)?,
+            fixture: fs::read_to_string(self.root.as_fd(), &self.state.fixture_path)?,
+        })
+    }
+    fn clean_build(&self) -> Result<(), io::Error> {
+        fs::remove_dir_all_at(self.root.as_fd(), c".cpcache")?;
+        Ok(())
+    }
+    fn clean_session(&self) -> Result<(), io::Error> {
+        fs::remove_dir_all_at(self.root.as_fd(), c".lsp")?;
+        fs::remove_dir_all_at(self.root.as_fd(), c".clj-kondo")?;
+        fs::remove_at(self.root.as_fd(), STATE_FILE)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::namespace;
+
+    #[test]
+    fn simple_ns() {
+        assert_eq!(namespace("(ns kata)\n(defn f [x] x)"), Some("kata"));
+        assert_eq!(
+            namespace("(ns kata-test\n  (:require [clojure.test :refer :all]))"),
+            Some("kata-test")
+        );
+    }
+
+    #[test]
+    fn comment_and_meta() {
+        assert_eq!(
+            namespace(";; comment\n(ns ^:no-doc my-kata.core (:require [clojure.string]))"),
+            Some("my-kata.core")
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use std::{ffi::CStr, io, path::Path};
+
+use rustix::fd::{AsFd, OwnedFd};
+
+use crate::{
+    util::{build_timeout, call_command_at, fs, slug_name},
+    Code, Config, WorkspaceObject,
+};
+
+const PRELOADED_FILE: &CStr = c"preloaded.lisp";
+const CODE_FILE: &CStr = c"solution.lisp";
+const FIXTURE_FILE: &CStr = c"tests.lisp";
+
+/// test library used by fixture and how asdf `test-op` runs it
+struct TestLibrary {
+    system: &'static str,
+    run_tests: &'static str,
+}
+
+const TEST_LIBRARIES: [TestLibrary; 3] = [
+    TestLibrary {
+        system: "fiveam",
+        run_tests: "(symbol-call :fiveam :run-all-tests)",
+    },
+    TestLibrary {
+        system: "lisp-unit",
+        run_tests: "(symbol-call :lisp-unit :run-tests :all)",
+    },
+    TestLibrary {
+        system: "rove",
+        run_tests: "(symbol-call :rove :run c)",
+    },
+];
+
+/// detect test library referenced by fixture, defaults to rove
+fn test_library(fixture: &str) -> &'static TestLibrary {
+    let fixture = fixture.to_ascii_lowercase();
+    TEST_LIBRARIES
+        .iter()
+        .find(|l| {
+            fixture.match_indices(l.system).any(|(idx, _)| {
+                // only match whole symbol like `:fiveam` or `#:fiveam`
+                let before = fixture[..idx].chars().next_back();
+                let after = fixture[idx + l.system.len()..].chars().next();
+                matches!(before, Some(':' | '"' | '(' | ' ' | '\n'))
+                    && matches!(after, Some(':' | '"' | ')' | ' ' | '\n'))
+            })
+        })
+        .unwrap_or(&TEST_LIBRARIES[2])
+}
+
+pub struct CommonLisp {
+    root: OwnedFd,
+}
+impl CommonLisp {
+    pub fn create(root: &Path, project: Config) -> io::Result<Self> {
+        let root = fs::open_dirfd(root)?;
+
+        fs::write(root.as_fd(), CODE_FILE, project.code)?;
+        fs::write(root.as_fd(), FIXTURE_FILE, project.fixture)?;
+        if project.has_preload {
+            fs::write(root.as_fd(), PRELOADED_FILE, "")?;
+        }
+
+        let package = slug_name(project.slug);
+        let test_library = test_library(project.fixture);
+        fs::write(
+            root.as_fd(),
+            format!("{package}.asd"),
+            format!(
+                include_str!("./commonlisp/challenge.asd"),
+                package = package,
+                preloaded = if project.has_preload {
+                    "(:file \"preloaded\") "
+                } else {
+                    ""
+                },
+                test_library = test_library.system,
+                run_tests = test_library.run_tests
+            ),
+        )?;
+
+        Ok(Self { root })
+    }
+    pub fn open(root: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self {
+            root: fs::open_dirfd(root.as_ref())?,
+        })
+    }
+}
+impl WorkspaceObject for CommonLisp {
+    fn get_code(&self) -> Result<Code, io::Error> {
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), CODE_FILE)?,
+            fixture: fs::read_to_string(self.root.as_fd(), FIXTURE_FILE)?,
+        })
+    }
+    fn clean_build(&self) -> Result<(), io::Error> {
+        // fasl files are stored in the asdf output translation of workspace dir
+        call_command_at(
+            self.root.as_fd(),
+            "sbcl",
+            [
+                "--non-interactive",
+                "--eval",
+                "(require :asdf)",
+                "--eval",
+                "(uiop:delete-directory-tree \
+                 (asdf:apply-output-translations (uiop:getcwd)) \
+                 :validate t :if-does-not-exist :ignore)",
+            ],
+            build_timeout(),
+        )?
+        .check()?;
+        Ok(())
+    }
+    fn clean_session(&self) -> Result<(), io::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::test_library;
+
+    #[test]
+    fn detect_library() {
+        assert_eq!(
+            test_library("(defpackage #:tests (:use #:cl #:fiveam #:solution))").system,
+            "fiveam"
+        );
+        assert_eq!(
+            test_library("(ql:quickload :lisp-unit)\n(in-package :lisp-unit)").system,
+            "lisp-unit"
+        );
+        assert_eq!(
+            test_library("(defpackage #:tests (:use #:cl))").system,
+            "rove"
+        );
+    }
+}
@@ -6,7 +6,7 @@ use rustix::{
 };
 
 use crate::{
-    util::{call_command_at, fs},
+    util::{build_timeout, call_command_at, fs},
     Code, Config, WorkspaceObject,
 };
 
@@ -80,7 +80,8 @@ impl WorkspaceObject for Coq {
         })
     }
     fn clean_build(&self) -> Result<(), io::Error> {
-        call_command_at(self.root.as_fd(), "make", ["clean"])
+        call_command_at(self.root.as_fd(), "make", ["clean"], build_timeout())?.check()?;
+        Ok(())
     }
     fn clean_session(&self) -> Result<(), io::Error> {
         Ok(())
@@ -0,0 +1,93 @@
+use std::{ffi::CStr, path::Path};
+
+use codewars_types::KnownLangId;
+use rustix::{
+    fd::{AsFd, OwnedFd},
+    io::Errno,
+};
+
+use crate::{
+    util::{fs, slug_name, toolchain},
+    Code, Config, SubstitutedToolchain, WorkspaceObject,
+};
+
+const PRELOADED_PATH: &CStr = c"src/preloaded.cr";
+const CODE_PATH: &CStr = c"src/solution.cr";
+// fixture is loaded by `spec/sample_spec.cr` after the solution
+const TEST_PATH: &CStr = c"spec/sample.cr";
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct OpenError(Errno);
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct CreateError(#[from] Errno);
+
+pub struct Crystal {
+    root: OwnedFd,
+}
+impl Crystal {
+    pub fn open(root: &Path) -> Result<Self, OpenError> {
+        Ok(Self {
+            root: fs::open_dirfd(root).map_err(OpenError)?,
+        })
+    }
+    pub fn create(
+        root: &Path,
+        project: Config,
+    ) -> Result<(Self, Option<SubstitutedToolchain>), CreateError> {
+        let (toolchain, substituted) = toolchain(KnownLangId::Crystal, project.version_id);
+        let root = fs::open_dirfd(root)?;
+
+        fs::mkdirat(root.as_fd(), c"src")?;
+        fs::write(root.as_fd(), CODE_PATH, project.code)?;
+        if project.has_preload {
+            fs::write(root.as_fd(), PRELOADED_PATH, "")?;
+        }
+
+        fs::mkdirat(root.as_fd(), c"spec")?;
+        fs::write(root.as_fd(), TEST_PATH, project.fixture)?;
+        fs::write(
+            root.as_fd(),
+            c"spec/sample_spec.cr",
+            format!(
+                include_str!("./crystal/sample_spec.cr"),
+                preloaded = if project.has_preload {
+                    "require \"../src/preloaded\"\n"
+                } else {
+                    ""
+                }
+            ),
+        )?;
+
+        fs::write(
+            root.as_fd(),
+            c"shard.yml",
+            format!(
+                include_str!("./crystal/shard.yml"),
+                package = slug_name(project.slug),
+                crystal_version = toolchain.version
+            ),
+        )?;
+
+        Ok((Self { root }, substituted))
+    }
+}
+impl WorkspaceObject for Crystal {
+    fn get_code(&self) -> Result<Code, std::io::Error> {
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), CODE_PATH)?,
+            fixture: fs::read_to_string(self.root.as_fd(), TEST_PATH)?,
+        })
+    }
+    fn clean_build(&self) -> Result<(), std::io::Error> {
+        fs::remove_dir_all_at(self.root.as_fd(), c"bin")?;
+        Ok(())
+    }
+    fn clean_session(&self) -> Result<(), std::io::Error> {
+        fs::remove_dir_all_at(self.root.as_fd(), c"lib")?;
+        fs::remove_at(self.root.as_fd(), c"shard.lock")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use std::path::Path;
+
+use codewars_types::KnownLangId;
+
+use crate::{
+    dotnet::{CreateErrorInner, Project, ProjectKind},
+    java::public_class,
+    util::{fs, toolchain},
+    Code, Config, SubstitutedToolchain, WorkspaceObject,
+};
+
+pub use crate::dotnet::{CreateError, OpenError};
+
+const PROJECT: ProjectKind = ProjectKind {
+    type_guid: "9A19103F-16F7-4668-BE54-9A1E7A4F7556",
+    project_ext: "csproj",
+    source_ext: "cs",
+};
+
+/// map codewars C# language version to target framework of its toolchain
+fn target_framework(version_id: &str) -> (&'static str, Option<SubstitutedToolchain>) {
+    let (toolchain, substituted) = toolchain(KnownLangId::CSharp, version_id);
+    let target_framework = toolchain
+        .option("target_framework")
+        .expect("dotnet toolchain without target framework");
+    (target_framework, substituted)
+}
+
+pub struct CSharp(Project);
+impl CSharp {
+    pub fn open(root: &Path) -> Result<Self, OpenError> {
+        Project::open(root).map(Self)
+    }
+    pub fn create(
+        root: &Path,
+        project: Config,
+    ) -> Result<(Self, Option<SubstitutedToolchain>), CreateError> {
+        let (target_framework, substituted) = target_framework(project.version_id);
+        let code_class = public_class(project.code).ok_or(CreateErrorInner::UnknownClassName)?;
+        let test_class = public_class(project.fixture).ok_or(CreateErrorInner::UnknownClassName)?;
+
+        Project::create(
+            root,
+            &PROJECT,
+            project.slug,
+            (code_class, project.code),
+            (test_class, project.fixture),
+            // sdk style projects compile every source file in project directory
+            project.has_preload.then_some(""),
+            |root, _, _| {
+                fs::write(
+                    root,
+                    c"Solution/Solution.csproj",
+                    format!(
+                        include_str!("./csharp/Solution.csproj"),
+                        target_framework = target_framework,
+                        lang_version = project.version_id
+                    ),
+                )?;
+                fs::write(
+                    root,
+                    c"Tests/Tests.csproj",
+                    format!(
+                        include_str!("./csharp/Tests.csproj"),
+                        target_framework = target_framework,
+                        lang_version = project.version_id
+                    ),
+                )
+            },
+        )
+        .map(|p| (Self(p), substituted))
+    }
+}
+impl WorkspaceObject for CSharp {
+    fn get_code(&self) -> Result<Code, std::io::Error> {
+        self.0.get_code()
+    }
+    fn clean_build(&self) -> Result<(), std::io::Error> {
+        self.0.clean_build()
+    }
+    fn clean_session(&self) -> Result<(), std::io::Error> {
+        self.0.clean_session()
+    }
+}
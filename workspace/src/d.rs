@@ -0,0 +1,80 @@
+use std::{ffi::CStr, path::Path};
+
+use codewars_types::KnownLangId;
+use rustix::{
+    fd::{AsFd, OwnedFd},
+    io::Errno,
+};
+
+use crate::{
+    util::{build_timeout, call_command_at, fs, slug_name, toolchain},
+    Code, Config, SubstitutedToolchain, WorkspaceObject,
+};
+
+const PRELOADED_PATH: &CStr = c"source/preloaded.d";
+const CODE_PATH: &CStr = c"source/solution.d";
+const TEST_PATH: &CStr = c"tests/sample.d";
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct OpenError(Errno);
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct CreateError(#[from] Errno);
+
+pub struct D {
+    root: OwnedFd,
+}
+impl D {
+    pub fn open(root: &Path) -> Result<Self, OpenError> {
+        Ok(Self {
+            root: fs::open_dirfd(root).map_err(OpenError)?,
+        })
+    }
+    pub fn create(
+        root: &Path,
+        project: Config,
+    ) -> Result<(Self, Option<SubstitutedToolchain>), CreateError> {
+        let (toolchain, substituted) = toolchain(KnownLangId::D, project.version_id);
+        let root = fs::open_dirfd(root)?;
+
+        fs::mkdirat(root.as_fd(), c"source")?;
+        fs::write(root.as_fd(), CODE_PATH, project.code)?;
+        if project.has_preload {
+            fs::write(root.as_fd(), PRELOADED_PATH, "module preloaded;\n")?;
+        }
+
+        fs::mkdirat(root.as_fd(), c"tests")?;
+        fs::write(root.as_fd(), TEST_PATH, project.fixture)?;
+
+        fs::write(
+            root.as_fd(),
+            c"dub.json",
+            format!(
+                include_str!("./d/dub.json"),
+                package = slug_name(project.slug),
+                frontend_version = toolchain.version
+            ),
+        )?;
+
+        Ok((Self { root }, substituted))
+    }
+}
+impl WorkspaceObject for D {
+    fn get_code(&self) -> Result<Code, std::io::Error> {
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), CODE_PATH)?,
+            fixture: fs::read_to_string(self.root.as_fd(), TEST_PATH)?,
+        })
+    }
+    fn clean_build(&self) -> Result<(), std::io::Error> {
+        call_command_at(self.root.as_fd(), "dub", ["clean"], build_timeout())?.check()?;
+        Ok(())
+    }
+    fn clean_session(&self) -> Result<(), std::io::Error> {
+        fs::remove_dir_all_at(self.root.as_fd(), c".dub")?;
+        fs::remove_at(self.root.as_fd(), c"dub.selections.json")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+use std::{ffi::CStr, path::Path};
+
+use codewars_types::KnownLangId;
+use rustix::{
+    fd::{AsFd, BorrowedFd, OwnedFd},
+    io::Errno,
+};
+
+use crate::{
+    util::{fs, toolchain},
+    Code, Config, SubstitutedToolchain, WorkspaceObject,
+};
+
+const PRELOADED_PATH: &CStr = c"lib/preloaded.dart";
+const CODE_PATH: &CStr = c"lib/solution.dart";
+const TEST_PATH: &CStr = c"test/solution_test.dart";
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct OpenError(#[from] Errno);
+
+#[derive(Debug, thiserror::Error)]
+enum CreateErrorInner {
+    #[error("invalid dart sdk version {0}")]
+    InvalidSdk(&'static str),
+    #[error("io error")]
+    Io(#[source] Errno),
+}
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct CreateError(#[from] CreateErrorInner);
+
+/// sdk constraint `>=version <next_major.0.0` for dart release like `3.3.4`
+fn sdk_constraint(version: &str) -> Option<(String, String)> {
+    let major = version.split('.').next()?.parse::<u16>().ok()?;
+    Some((version.to_string(), format!("{}.0.0", major + 1)))
+}
+
+pub struct Dart {
+    root: OwnedFd,
+}
+impl Dart {
+    pub fn open(root: &Path) -> Result<Self, OpenError> {
+        Ok(Self {
+            root: fs::open_dirfd(root)?,
+        })
+    }
+    pub fn create(
+        root: &Path,
+        project: Config,
+    ) -> Result<(Self, Option<SubstitutedToolchain>), CreateError> {
+        let (toolchain, substituted) = toolchain(KnownLangId::Dart, project.version_id);
+        let (sdk_min, sdk_max) = sdk_constraint(toolchain.version)
+            .ok_or(CreateErrorInner::InvalidSdk(toolchain.version))?;
+        let root = fs::open_dirfd(root).map_err(CreateErrorInner::Io)?;
+
+        fn inner(root: BorrowedFd, project: Config, sdk: (String, String)) -> Result<(), Errno> {
+            fs::write(
+                root,
+                c"pubspec.yaml",
+                format!(
+                    include_str!("./dart/pubspec.yaml"),
+                    sdk_min = sdk.0,
+                    sdk_max = sdk.1
+                ),
+            )?;
+
+            fs::mkdirat(root, c"lib")?;
+            fs::write(root, CODE_PATH, project.code)?;
+            if project.has_preload {
+                fs::write(root, PRELOADED_PATH, "")?;
+            }
+
+            fs::mkdirat(root, c"test")?;
+            fs::write(root, TEST_PATH, project.fixture)?;
+
+            Ok(())
+        }
+        inner(root.as_fd(), project, (sdk_min, sdk_max)).map_err(CreateErrorInner::Io)?;
+
+        Ok((Self { root }, substituted))
+    }
+}
+impl WorkspaceObject for Dart {
+    fn get_code(&self) -> Result<Code, std::io::Error> {
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), CODE_PATH)?,
+            fixture: fs::read_to_string(self.root.as_fd(), TEST_PATH)?,
+        })
+    }
+    fn clean_build(&self) -> Result<(), std::io::Error> {
+        fs::remove_dir_all_at(self.root.as_fd(), c"build")?;
+        Ok(())
+    }
+    fn clean_session(&self) -> Result<(), std::io::Error> {
+        fs::remove_dir_all_at(self.root.as_fd(), c".dart_tool")?;
+        fs::remove_at(self.root.as_fd(), c"pubspec.lock")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::sdk_constraint;
+
+    #[test]
+    fn constraint() {
+        assert_eq!(
+            sdk_constraint("3.3.4"),
+            Some(("3.3.4".to_string(), "4.0.0".to_string()))
+        );
+        assert_eq!(sdk_constraint("latest"), None);
+    }
+}
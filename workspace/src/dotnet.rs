@@ -0,0 +1,158 @@
+use std::{
+    ffi::{CStr, CString},
+    io,
+    path::Path,
+};
+
+use rustix::{
+    fd::{AsFd, BorrowedFd, OwnedFd},
+    io::Errno,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{util::fs, Code};
+
+const STATE_PATH: &CStr = c"state.json";
+
+#[derive(Debug, thiserror::Error)]
+enum OpenErrorInner {
+    #[error("io error")]
+    Io(#[source] Errno),
+    #[error("failed to parse json")]
+    Json(#[source] serde_json::Error),
+}
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct OpenError(#[from] OpenErrorInner);
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum CreateErrorInner {
+    #[error("failed to get class name")]
+    UnknownClassName,
+    #[error("failed to write source code")]
+    WriteCode(#[source] io::Error),
+    #[error("io error")]
+    Io(#[source] Errno),
+}
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct CreateError(#[from] CreateErrorInner);
+
+/// Kind of sdk style project
+pub(crate) struct ProjectKind {
+    /// project type guid used in solution file
+    pub type_guid: &'static str,
+    /// project file extension
+    pub project_ext: &'static str,
+    /// source file extension
+    pub source_ext: &'static str,
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    code_path: CString,
+    fixture_path: CString,
+}
+
+/// dotnet solution with a library project `Solution` and a test project `Tests`
+pub(crate) struct Project {
+    root: OwnedFd,
+    state: State,
+}
+impl Project {
+    pub fn open(root: &Path) -> Result<Self, OpenError> {
+        let root = fs::open_dirfd(root).map_err(OpenErrorInner::Io)?;
+        Ok(Self {
+            state: serde_json::from_slice(
+                &fs::read(root.as_fd(), STATE_PATH).map_err(OpenErrorInner::Io)?,
+            )
+            .map_err(OpenErrorInner::Json)?,
+            root,
+        })
+    }
+
+    /// Write solution file and sources. Project files are written by `write_projects`
+    /// with the name of code and test file.
+    pub fn create(
+        root: &Path,
+        kind: &ProjectKind,
+        slug: &str,
+        (code_name, code): (&str, &str),
+        (test_name, fixture): (&str, &str),
+        preloaded: Option<&str>,
+        write_projects: impl FnOnce(BorrowedFd, &str, &str) -> Result<(), Errno>,
+    ) -> Result<Self, CreateError> {
+        fn write_source(root: BorrowedFd, path: String, code: &str) -> io::Result<CString> {
+            let path =
+                CString::new(path).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            fs::write(root, &path, code)?;
+            Ok(path)
+        }
+
+        let root = fs::open_dirfd(root).map_err(CreateErrorInner::Io)?;
+
+        fs::write(
+            root.as_fd(),
+            format!("{slug}.sln"),
+            format!(
+                include_str!("./dotnet/challenge.sln"),
+                project_type = kind.type_guid,
+                project_ext = kind.project_ext
+            ),
+        )
+        .map_err(CreateErrorInner::Io)?;
+
+        let code_file = format!("{code_name}.{}", kind.source_ext);
+        let test_file = format!("{test_name}.{}", kind.source_ext);
+
+        fs::mkdirat(root.as_fd(), c"Solution").map_err(CreateErrorInner::Io)?;
+        let code_path = write_source(root.as_fd(), format!("Solution/{code_file}"), code)
+            .map_err(CreateErrorInner::WriteCode)?;
+
+        if let Some(preloaded) = preloaded {
+            write_source(
+                root.as_fd(),
+                format!("Solution/Preloaded.{}", kind.source_ext),
+                preloaded,
+            )
+            .map_err(CreateErrorInner::WriteCode)?;
+        }
+
+        fs::mkdirat(root.as_fd(), c"Tests").map_err(CreateErrorInner::Io)?;
+        let fixture_path = write_source(root.as_fd(), format!("Tests/{test_file}"), fixture)
+            .map_err(CreateErrorInner::WriteCode)?;
+
+        write_projects(root.as_fd(), &code_file, &test_file).map_err(CreateErrorInner::Io)?;
+
+        let state = State {
+            code_path,
+            fixture_path,
+        };
+        fs::write(
+            root.as_fd(),
+            STATE_PATH,
+            serde_json::to_vec(&state).unwrap(),
+        )
+        .map_err(CreateErrorInner::Io)?;
+
+        Ok(Self { root, state })
+    }
+
+    pub fn get_code(&self) -> Result<Code, io::Error> {
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), &self.state.code_path)?,
+            fixture: fs::read_to_string(self.root.as_fd(), &self.state.fixture_path)?,
+        })
+    }
+    pub fn clean_build(&self) -> Result<(), io::Error> {
+        for dir in [c"Solution/bin", c"Solution/obj", c"Tests/bin", c"Tests/obj"] {
+            fs::remove_dir_all_at(self.root.as_fd(), dir)?;
+        }
+        Ok(())
+    }
+    pub fn clean_session(&self) -> Result<(), io::Error> {
+        fs::remove_dir_all_at(self.root.as_fd(), c".vs")?;
+        fs::remove_at(self.root.as_fd(), STATE_PATH)?;
+        Ok(())
+    }
+}
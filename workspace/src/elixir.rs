@@ -0,0 +1,216 @@
+use std::{
+    ffi::{CStr, CString},
+    io,
+    path::Path,
+};
+
+use codewars_types::KnownLangId;
+use rustix::{
+    fd::{AsFd, OwnedFd},
+    io::Errno,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    util::{build_timeout, call_command_at, fs, package_name, toolchain, write_module},
+    Code, Config, SubstitutedToolchain, WorkspaceObject,
+};
+
+const STATE_FILE: &CStr = c"elixir_state.json";
+
+/// get name of the first module defined
+fn module_name(src: &str) -> Option<&str> {
+    let mut s = src.trim_start();
+    while s.starts_with('#') {
+        s = s.split_once('\n')?.1.trim_start();
+    }
+    let s = s.strip_prefix("defmodule")?.trim_start();
+    let end = s
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .unwrap_or(s.len());
+    (end != 0).then(|| &s[..end])
+}
+
+/// convert module name to dot separated file name like `Macro.underscore`
+fn underscore(module: &str) -> String {
+    let mut ret = String::with_capacity(module.len() + 4);
+    for (idx, seg) in module.split('.').enumerate() {
+        if idx != 0 {
+            ret.push('.');
+        }
+        let chars = seg.chars().collect::<Vec<_>>();
+        for (i, c) in chars.iter().enumerate() {
+            if c.is_uppercase() {
+                let prev_lower =
+                    i > 0 && (chars[i - 1].is_lowercase() || chars[i - 1].is_numeric());
+                let next_lower = i > 0
+                    && chars[i - 1].is_uppercase()
+                    && chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+                if prev_lower || next_lower {
+                    ret.push('_');
+                }
+                ret.extend(c.to_lowercase());
+            } else {
+                ret.push(*c);
+            }
+        }
+    }
+    ret
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    code_path: CString,
+    fixture_path: CString,
+}
+
+#[derive(Debug, thiserror::Error)]
+enum OpenErrInner {
+    #[error("failed to open state")]
+    Io(#[source] Errno),
+    #[error("failed to deserialize json")]
+    Json(#[source] serde_json::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct OpenError(#[from] OpenErrInner);
+
+#[derive(Debug, thiserror::Error)]
+enum CreateErrorInner {
+    #[error("unknown code {0}")]
+    UnknownCode(String),
+    #[error("unknown test {0}")]
+    UnknownTest(String),
+    #[error("failed to write source code")]
+    WriteCode(#[source] io::Error),
+    #[error("io error")]
+    Io(#[source] Errno),
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct CreateError(#[from] CreateErrorInner);
+
+pub struct Elixir {
+    root: OwnedFd,
+    state: State,
+}
+
+impl Elixir {
+    pub fn open(root: &Path) -> Result<Self, OpenError> {
+        let root = fs::open_dirfd(root).map_err(OpenErrInner::Io)?;
+        Ok(Self {
+            state: serde_json::from_slice(
+                &fs::read(root.as_fd(), STATE_FILE).map_err(OpenErrInner::Io)?,
+            )
+            .map_err(OpenErrInner::Json)?,
+            root,
+        })
+    }
+    pub fn create(
+        root: &Path,
+        project: Config,
+    ) -> Result<(Self, Option<SubstitutedToolchain>), CreateError> {
+        let (toolchain, substituted) = toolchain(KnownLangId::Elixir, project.version_id);
+        let root = fs::open_dirfd(root).map_err(CreateErrorInner::Io)?;
+
+        let code_mod = module_name(project.code)
+            .ok_or_else(|| CreateErrorInner::UnknownCode(project.code.to_string()))?;
+        let code_path = write_module(
+            root.as_fd(),
+            "lib",
+            &underscore(code_mod),
+            "ex",
+            project.code,
+        )
+        .map_err(CreateErrorInner::WriteCode)?;
+
+        let test_mod = module_name(project.fixture)
+            .ok_or_else(|| CreateErrorInner::UnknownTest(project.fixture.to_string()))?;
+        // mix only runs test files ending with `_test.exs`
+        let mut test_file = underscore(test_mod);
+        if !test_file.ends_with("_test") {
+            test_file.push_str("_test");
+        }
+        let fixture_path = write_module(root.as_fd(), "test", &test_file, "exs", project.fixture)
+            .map_err(CreateErrorInner::WriteCode)?;
+
+        fs::write(
+            root.as_fd(),
+            c"test/test_helper.exs",
+            include_str!("./elixir/test_helper.exs"),
+        )
+        .map_err(CreateErrorInner::Io)?;
+
+        if project.has_preload {
+            fs::write(root.as_fd(), c"lib/preloaded.ex", "").map_err(CreateErrorInner::Io)?;
+        }
+
+        fs::write(
+            root.as_fd(),
+            c"mix.exs",
+            format!(
+                include_str!("./elixir/mix.exs"),
+                app = package_name(project.slug),
+                elixir_version = toolchain.version
+            ),
+        )
+        .map_err(CreateErrorInner::Io)?;
+
+        let state = State {
+            code_path,
+            fixture_path,
+        };
+        fs::write(
+            root.as_fd(),
+            STATE_FILE,
+            serde_json::to_vec(&state).unwrap(),
+        )
+        .map_err(CreateErrorInner::Io)?;
+
+        Ok((Self { root, state }, substituted))
+    }
+}
+impl WorkspaceObject for Elixir {
+    fn get_code(&self) -> Result<Code, io::Error> {
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), &self.state.code_path)?,
+            fixture: fs::read_to_string(self.root.as_fd(), &self.state.fixture_path)?,
+        })
+    }
+    fn clean_build(&self) -> Result<(), io::Error> {
+        call_command_at(self.root.as_fd(), "mix", ["clean"], build_timeout())?.check()?;
+        Ok(())
+    }
+    fn clean_session(&self) -> Result<(), io::Error> {
+        fs::remove_dir_all_at(self.root.as_fd(), c"_build")?;
+        fs::remove_dir_all_at(self.root.as_fd(), c"deps")?;
+        fs::remove_at(self.root.as_fd(), c"mix.lock")?;
+        fs::remove_at(self.root.as_fd(), STATE_FILE)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{module_name, underscore};
+
+    #[test]
+    fn simple_module() {
+        assert_eq!(module_name("defmodule Kata do\nend"), Some("Kata"));
+        assert_eq!(
+            module_name("# comment\ndefmodule Kata.Solution do\nend"),
+            Some("Kata.Solution")
+        );
+        assert_eq!(module_name("defmodule Kata"), Some("Kata"));
+        assert_eq!(module_name("defmodule "), None);
+    }
+
+    #[test]
+    fn underscore_name() {
+        assert_eq!(underscore("TestSolution"), "test_solution");
+        assert_eq!(underscore("Kata.HTTPClient"), "kata.http_client");
+        assert_eq!(underscore("Base64Test"), "base64_test");
+    }
+}
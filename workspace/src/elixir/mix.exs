@@ -0,0 +1,12 @@
+defmodule Challenge.MixProject do
+  use Mix.Project
+
+  def project do
+    [
+      app: :{app},
+      version: "0.1.0",
+      elixir: "~> {elixir_version}",
+      deps: []
+    ]
+  end
+end
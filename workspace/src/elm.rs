@@ -0,0 +1,175 @@
+use std::{
+    ffi::{CStr, CString},
+    io,
+    path::Path,
+};
+
+use codewars_types::KnownLangId;
+use rustix::{
+    fd::{AsFd, OwnedFd},
+    io::Errno,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    haskell::skip_header_comments,
+    util::{fs, toolchain, write_module},
+    Code, Config, SubstitutedToolchain, WorkspaceObject,
+};
+
+const STATE_FILE: &CStr = c"elm_state.json";
+const PRELOADED_PATH: &CStr = c"src/Preloaded.elm";
+
+/// get module name, allowing `port` and `effect` modules
+fn module_name(src: &str) -> Option<&str> {
+    let s = skip_header_comments(src)?;
+    let s = s
+        .strip_prefix("port ")
+        .or_else(|| s.strip_prefix("effect "))
+        .map_or(s, str::trim_start);
+    Some(
+        s.strip_prefix("module")?
+            .trim_start()
+            .split_once(char::is_whitespace)?
+            .0,
+    )
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    code_path: CString,
+    fixture_path: CString,
+}
+
+#[derive(Debug, thiserror::Error)]
+enum OpenErrInner {
+    #[error("failed to open state")]
+    Io(#[source] Errno),
+    #[error("failed to deserialize json")]
+    Json(#[source] serde_json::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct OpenError(#[from] OpenErrInner);
+
+#[derive(Debug, thiserror::Error)]
+enum CreateErrorInner {
+    #[error("unknown code {0}")]
+    UnknownCode(String),
+    #[error("unknown test {0}")]
+    UnknownTest(String),
+    #[error("failed to write source code")]
+    WriteCode(#[source] io::Error),
+    #[error("io error")]
+    Io(#[source] Errno),
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct CreateError(#[from] CreateErrorInner);
+
+pub struct Elm {
+    root: OwnedFd,
+    state: State,
+}
+
+impl Elm {
+    pub fn open(root: &Path) -> Result<Self, OpenError> {
+        let root = fs::open_dirfd(root).map_err(OpenErrInner::Io)?;
+        Ok(Self {
+            state: serde_json::from_slice(
+                &fs::read(root.as_fd(), STATE_FILE).map_err(OpenErrInner::Io)?,
+            )
+            .map_err(OpenErrInner::Json)?,
+            root,
+        })
+    }
+    pub fn create(
+        root: &Path,
+        project: Config,
+    ) -> Result<(Self, Option<SubstitutedToolchain>), CreateError> {
+        let (toolchain, substituted) = toolchain(KnownLangId::Elm, project.version_id);
+        let root = fs::open_dirfd(root).map_err(CreateErrorInner::Io)?;
+
+        let code_mod = module_name(project.code)
+            .ok_or_else(|| CreateErrorInner::UnknownCode(project.code.to_string()))?;
+        let code_path = write_module(root.as_fd(), "src", code_mod, "elm", project.code)
+            .map_err(CreateErrorInner::WriteCode)?;
+
+        if project.has_preload {
+            fs::write(
+                root.as_fd(),
+                PRELOADED_PATH,
+                "module Preloaded exposing (..)\n",
+            )
+            .map_err(CreateErrorInner::Io)?;
+        }
+
+        let test_mod = module_name(project.fixture)
+            .ok_or_else(|| CreateErrorInner::UnknownTest(project.fixture.to_string()))?;
+        let fixture_path = write_module(root.as_fd(), "tests", test_mod, "elm", project.fixture)
+            .map_err(CreateErrorInner::WriteCode)?;
+
+        fs::write(
+            root.as_fd(),
+            c"elm.json",
+            format!(
+                include_str!("./elm/elm.json"),
+                elm_version = toolchain.version
+            ),
+        )
+        .map_err(CreateErrorInner::Io)?;
+
+        let state = State {
+            code_path,
+            fixture_path,
+        };
+        fs::write(
+            root.as_fd(),
+            STATE_FILE,
+            serde_json::to_vec(&state).unwrap(),
+        )
+        .map_err(CreateErrorInner::Io)?;
+
+        Ok((Self { root, state }, substituted))
+    }
+}
+impl WorkspaceObject for Elm {
+    fn get_code(&self) -> Result<Code, io::Error> {
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), &self.state.code_path)?,
+            fixture: fs::read_to_string(self.root.as_fd(), &self.state.fixture_path)?,
+        })
+    }
+    fn clean_build(&self) -> Result<(), io::Error> {
+        fs::remove_dir_all_at(self.root.as_fd(), c"elm-stuff")?;
+        Ok(())
+    }
+    fn clean_session(&self) -> Result<(), io::Error> {
+        fs::remove_at(self.root.as_fd(), STATE_FILE)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::module_name;
+
+    #[test]
+    fn simple() {
+        assert_eq!(module_name("module Kata exposing (..)"), Some("Kata"));
+        assert_eq!(
+            module_name("-- comment\nmodule Kata.Solution exposing (f)"),
+            Some("Kata.Solution")
+        );
+    }
+
+    #[test]
+    fn port_module() {
+        assert_eq!(
+            module_name("{- doc -}\nport module Ports exposing (..)"),
+            Some("Ports")
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use std::{
+    ffi::{CStr, CString},
+    io,
+    path::Path,
+};
+
+use codewars_types::KnownLangId;
+use rustix::{
+    fd::{AsFd, OwnedFd},
+    io::Errno,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    util::{build_timeout, call_command_at, fs, package_name, toolchain, write_module},
+    Code, Config, SubstitutedToolchain, WorkspaceObject,
+};
+
+const STATE_FILE: &CStr = c"erlang_state.json";
+
+/// get module name from `-module(name).` attribute
+fn module_name(src: &str) -> Option<&str> {
+    src.lines().find_map(|l| {
+        let t = l.trim_start().strip_prefix("-module")?.trim_start();
+        Some(t.strip_prefix('(')?.split_once(')')?.0.trim())
+    })
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    code_path: CString,
+    fixture_path: CString,
+}
+
+#[derive(Debug, thiserror::Error)]
+enum OpenErrInner {
+    #[error("failed to open state")]
+    Io(#[source] Errno),
+    #[error("failed to deserialize json")]
+    Json(#[source] serde_json::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct OpenError(#[from] OpenErrInner);
+
+#[derive(Debug, thiserror::Error)]
+enum CreateErrorInner {
+    #[error("unknown code {0}")]
+    UnknownCode(String),
+    #[error("unknown test {0}")]
+    UnknownTest(String),
+    #[error("failed to write source code")]
+    WriteCode(#[source] io::Error),
+    #[error("io error")]
+    Io(#[source] Errno),
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct CreateError(#[from] CreateErrorInner);
+
+pub struct Erlang {
+    root: OwnedFd,
+    state: State,
+}
+
+impl Erlang {
+    pub fn open(root: &Path) -> Result<Self, OpenError> {
+        let root = fs::open_dirfd(root).map_err(OpenErrInner::Io)?;
+        Ok(Self {
+            state: serde_json::from_slice(
+                &fs::read(root.as_fd(), STATE_FILE).map_err(OpenErrInner::Io)?,
+            )
+            .map_err(OpenErrInner::Json)?,
+            root,
+        })
+    }
+    pub fn create(
+        root: &Path,
+        project: Config,
+    ) -> Result<(Self, Option<SubstitutedToolchain>), CreateError> {
+        let (toolchain, substituted) = toolchain(KnownLangId::Erlang, project.version_id);
+        let root = fs::open_dirfd(root).map_err(CreateErrorInner::Io)?;
+
+        let code_mod = module_name(project.code)
+            .ok_or_else(|| CreateErrorInner::UnknownCode(project.code.to_string()))?;
+        let code_path = write_module(root.as_fd(), "src", code_mod, "erl", project.code)
+            .map_err(CreateErrorInner::WriteCode)?;
+
+        let test_mod = module_name(project.fixture)
+            .ok_or_else(|| CreateErrorInner::UnknownTest(project.fixture.to_string()))?;
+        let fixture_path = write_module(root.as_fd(), "test", test_mod, "erl", project.fixture)
+            .map_err(CreateErrorInner::WriteCode)?;
+
+        if project.has_preload {
+            fs::write(root.as_fd(), c"src/preloaded.erl", "-module(preloaded).\n")
+                .map_err(CreateErrorInner::Io)?;
+        }
+
+        let app = package_name(project.slug);
+        fs::write(
+            root.as_fd(),
+            format!("src/{app}.app.src"),
+            format!(include_str!("./erlang/challenge.app.src"), app = app),
+        )
+        .map_err(CreateErrorInner::Io)?;
+        fs::write(
+            root.as_fd(),
+            c"rebar.config",
+            format!(
+                include_str!("./erlang/rebar.config"),
+                otp_version = toolchain.version
+            ),
+        )
+        .map_err(CreateErrorInner::Io)?;
+
+        let state = State {
+            code_path,
+            fixture_path,
+        };
+        fs::write(
+            root.as_fd(),
+            STATE_FILE,
+            serde_json::to_vec(&state).unwrap(),
+        )
+        .map_err(CreateErrorInner::Io)?;
+
+        Ok((Self { root, state }, substituted))
+    }
+}
+impl WorkspaceObject for Erlang {
+    fn get_code(&self) -> Result<Code, io::Error> {
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), &self.state.code_path)?,
+            fixture: fs::read_to_string(self.root.as_fd(), &self.state.fixture_path)?,
+        })
+    }
+    fn clean_build(&self) -> Result<(), io::Error> {
+        call_command_at(self.root.as_fd(), "rebar3", ["clean"], build_timeout())?.check()?;
+        Ok(())
+    }
+    fn clean_session(&self) -> Result<(), io::Error> {
+        fs::remove_dir_all_at(self.root.as_fd(), c"_build")?;
+        fs::remove_at(self.root.as_fd(), c"rebar.lock")?;
+        fs::remove_at(self.root.as_fd(), STATE_FILE)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::module_name;
+
+    #[test]
+    fn simple_module() {
+        assert_eq!(
+            module_name("% comment\n-module(kata).\n-export([f/1])."),
+            Some("kata")
+        );
+        assert_eq!(module_name("-module( kata_tests )."), Some("kata_tests"));
+    }
+}
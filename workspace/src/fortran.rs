@@ -0,0 +1,74 @@
+use std::{ffi::CStr, io, path::Path};
+
+use rustix::fd::{AsFd, OwnedFd};
+
+use crate::{
+    util::{build_timeout, call_command_at, fs, package_name},
+    Code, Config, WorkspaceObject,
+};
+
+const PRELOADED_FILE: &CStr = c"src/preloaded.f90";
+const CODE_FILE: &CStr = c"src/solution.f90";
+const FIXTURE_FILE: &CStr = c"test/sample.f90";
+
+pub struct Fortran {
+    root: OwnedFd,
+}
+impl Fortran {
+    pub fn create(root: &Path, project: Config) -> io::Result<Self> {
+        let root = fs::open_dirfd(root)?;
+
+        fs::mkdirat(root.as_fd(), c"src")?;
+        fs::write(root.as_fd(), CODE_FILE, project.code)?;
+        if project.has_preload {
+            fs::write(root.as_fd(), PRELOADED_FILE, "")?;
+        }
+        fs::write(
+            root.as_fd(),
+            c"src/cw2.f90",
+            include_str!("./fortran/cw2.f90"),
+        )?;
+
+        fs::mkdirat(root.as_fd(), c"test")?;
+        fs::write(root.as_fd(), FIXTURE_FILE, project.fixture)?;
+
+        fs::write(
+            root.as_fd(),
+            c"fpm.toml",
+            format!(
+                include_str!("./fortran/fpm.toml"),
+                package = package_name(project.slug)
+            ),
+        )?;
+
+        Ok(Self { root })
+    }
+    pub fn open(root: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self {
+            root: fs::open_dirfd(root.as_ref())?,
+        })
+    }
+}
+impl WorkspaceObject for Fortran {
+    fn get_code(&self) -> Result<Code, io::Error> {
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), CODE_FILE)?,
+            fixture: fs::read_to_string(self.root.as_fd(), FIXTURE_FILE)?,
+        })
+    }
+    fn clean_build(&self) -> Result<(), io::Error> {
+        // keep fetched dependencies
+        call_command_at(
+            self.root.as_fd(),
+            "fpm",
+            ["clean", "--skip"],
+            build_timeout(),
+        )?
+        .check()?;
+        Ok(())
+    }
+    fn clean_session(&self) -> Result<(), io::Error> {
+        fs::remove_dir_all_at(self.root.as_fd(), c"build")?;
+        Ok(())
+    }
+}
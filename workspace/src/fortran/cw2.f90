@@ -0,0 +1,104 @@
+! Minimal local implementation of the Codewars `CW2` test module.
+module CW2
+  implicit none
+  private
+  public :: describe, it, assertEquals, assertNotEquals, assertTrue, assertFalse
+
+  interface assertEquals
+    module procedure assertEqualsInt, assertEqualsReal, assertEqualsLogical, assertEqualsChar
+  end interface
+
+  interface assertNotEquals
+    module procedure assertNotEqualsInt, assertNotEqualsChar
+  end interface
+
+contains
+
+  subroutine describe(msg)
+    character(*), intent(in) :: msg
+    print '(a)', msg
+  end subroutine
+
+  subroutine it(msg)
+    character(*), intent(in) :: msg
+    print '(2x,a)', msg
+  end subroutine
+
+  subroutine report(passed, msg)
+    logical, intent(in) :: passed
+    character(*), intent(in) :: msg
+    if (passed) then
+      print '(4x,a)', 'Test Passed'
+    else
+      print '(4x,a)', 'FAILED: ' // msg
+      error stop 1
+    end if
+  end subroutine
+
+  subroutine assertEqualsInt(expected, actual, msg)
+    integer, intent(in) :: expected, actual
+    character(*), intent(in), optional :: msg
+    character(64) :: buf
+    write (buf, '(a,i0,a,i0)') 'Expected ', expected, ', got ', actual
+    call report(expected == actual, message(msg, buf))
+  end subroutine
+
+  subroutine assertEqualsReal(expected, actual, msg)
+    real(8), intent(in) :: expected, actual
+    character(*), intent(in), optional :: msg
+    character(96) :: buf
+    write (buf, '(a,g0,a,g0)') 'Expected ', expected, ', got ', actual
+    call report(abs(expected - actual) <= 1d-9 * max(1d0, abs(expected)), message(msg, buf))
+  end subroutine
+
+  subroutine assertEqualsLogical(expected, actual, msg)
+    logical, intent(in) :: expected, actual
+    character(*), intent(in), optional :: msg
+    character(64) :: buf
+    write (buf, '(a,l1,a,l1)') 'Expected ', expected, ', got ', actual
+    call report(expected .eqv. actual, message(msg, buf))
+  end subroutine
+
+  subroutine assertEqualsChar(expected, actual, msg)
+    character(*), intent(in) :: expected, actual
+    character(*), intent(in), optional :: msg
+    call report(expected == actual, &
+      message(msg, 'Expected "' // expected // '", got "' // actual // '"'))
+  end subroutine
+
+  subroutine assertNotEqualsInt(unexpected, actual, msg)
+    integer, intent(in) :: unexpected, actual
+    character(*), intent(in), optional :: msg
+    call report(unexpected /= actual, message(msg, 'Values are equal'))
+  end subroutine
+
+  subroutine assertNotEqualsChar(unexpected, actual, msg)
+    character(*), intent(in) :: unexpected, actual
+    character(*), intent(in), optional :: msg
+    call report(unexpected /= actual, message(msg, 'Values are equal'))
+  end subroutine
+
+  subroutine assertTrue(actual, msg)
+    logical, intent(in) :: actual
+    character(*), intent(in), optional :: msg
+    call report(actual, message(msg, 'Expected true'))
+  end subroutine
+
+  subroutine assertFalse(actual, msg)
+    logical, intent(in) :: actual
+    character(*), intent(in), optional :: msg
+    call report(.not. actual, message(msg, 'Expected false'))
+  end subroutine
+
+  function message(msg, default) result(ret)
+    character(*), intent(in), optional :: msg
+    character(*), intent(in) :: default
+    character(:), allocatable :: ret
+    if (present(msg)) then
+      ret = msg
+    else
+      ret = trim(default)
+    end if
+  end function
+
+end module CW2
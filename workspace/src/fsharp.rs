@@ -0,0 +1,140 @@
+use std::path::Path;
+
+use codewars_types::KnownLangId;
+
+use crate::{
+    dotnet::{CreateErrorInner, Project, ProjectKind},
+    util::{fs, toolchain},
+    Code, Config, SubstitutedToolchain, WorkspaceObject,
+};
+
+pub use crate::dotnet::{CreateError, OpenError};
+
+const PROJECT: ProjectKind = ProjectKind {
+    type_guid: "6EC3EE1D-3C4E-46DD-8F32-0CC8E7565705",
+    project_ext: "fsproj",
+    source_ext: "fs",
+};
+
+/// map codewars F# language version to target framework of its toolchain
+fn target_framework(version_id: &str) -> (&'static str, Option<SubstitutedToolchain>) {
+    let (toolchain, substituted) = toolchain(KnownLangId::FSharp, version_id);
+    let target_framework = toolchain
+        .option("target_framework")
+        .expect("dotnet toolchain without target framework");
+    (target_framework, substituted)
+}
+
+/// get top level module or namespace name
+fn module_name(src: &str) -> Option<&str> {
+    let mut s = src.trim_start();
+    loop {
+        if let Some(t) = s.strip_prefix("(*") {
+            s = t.split_once("*)")?.1.trim_start();
+        } else if s.starts_with("//") || s.starts_with('#') {
+            s = s.split_once('\n')?.1.trim_start();
+        } else {
+            break;
+        }
+    }
+    let s = s
+        .strip_prefix("module")
+        .or_else(|| s.strip_prefix("namespace"))?
+        .trim_start();
+    let s = s.strip_prefix("rec ").map_or(s, str::trim_start);
+    let name = &s[..s
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .unwrap_or(s.len())];
+    if name.is_empty() {
+        None
+    } else {
+        Some(name)
+    }
+}
+
+pub struct FSharp(Project);
+impl FSharp {
+    pub fn open(root: &Path) -> Result<Self, OpenError> {
+        Project::open(root).map(Self)
+    }
+    pub fn create(
+        root: &Path,
+        project: Config,
+    ) -> Result<(Self, Option<SubstitutedToolchain>), CreateError> {
+        let (target_framework, substituted) = target_framework(project.version_id);
+        let code_mod = module_name(project.code).ok_or(CreateErrorInner::UnknownClassName)?;
+        let test_mod = module_name(project.fixture).ok_or(CreateErrorInner::UnknownClassName)?;
+
+        Project::create(
+            root,
+            &PROJECT,
+            project.slug,
+            (code_mod, project.code),
+            (test_mod, project.fixture),
+            project.has_preload.then_some("module Preloaded\n"),
+            |root, code_file, test_file| {
+                fs::write(
+                    root,
+                    c"Solution/Solution.fsproj",
+                    format!(
+                        include_str!("./fsharp/Solution.fsproj"),
+                        target_framework = target_framework,
+                        lang_version = project.version_id,
+                        code_file = code_file,
+                        preloaded = if project.has_preload {
+                            "<Compile Include=\"Preloaded.fs\" />\n    "
+                        } else {
+                            ""
+                        }
+                    ),
+                )?;
+                fs::write(
+                    root,
+                    c"Tests/Tests.fsproj",
+                    format!(
+                        include_str!("./fsharp/Tests.fsproj"),
+                        target_framework = target_framework,
+                        lang_version = project.version_id,
+                        test_file = test_file
+                    ),
+                )
+            },
+        )
+        .map(|p| (Self(p), substituted))
+    }
+}
+impl WorkspaceObject for FSharp {
+    fn get_code(&self) -> Result<Code, std::io::Error> {
+        self.0.get_code()
+    }
+    fn clean_build(&self) -> Result<(), std::io::Error> {
+        self.0.clean_build()
+    }
+    fn clean_session(&self) -> Result<(), std::io::Error> {
+        self.0.clean_session()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::module_name;
+
+    #[test]
+    fn simple() {
+        assert_eq!(module_name("module Kata\n\nlet f x = x"), Some("Kata"));
+        assert_eq!(module_name("module Solution.Kata"), Some("Solution.Kata"));
+    }
+
+    #[test]
+    fn comment() {
+        assert_eq!(
+            module_name("// comment\n(* block\n comment *)\nmodule Tests"),
+            Some("Tests")
+        );
+    }
+
+    #[test]
+    fn namespace() {
+        assert_eq!(module_name("namespace rec Kata\n"), Some("Kata"));
+    }
+}
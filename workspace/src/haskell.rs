@@ -4,36 +4,54 @@ use std::{
     path::Path,
 };
 
+use codewars_types::KnownLangId;
 use rustix::{
-    fd::{AsFd, BorrowedFd, OwnedFd},
+    fd::{AsFd, OwnedFd},
     io::Errno,
 };
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    util::{call_command_at, fs},
-    Code, Config, WorkspaceObject,
+    util::{build_timeout, call_command_at, fs, toolchain, write_module},
+    Code, Config, SubstitutedToolchain, WorkspaceObject,
 };
 
 const STATE_FILE: &CStr = c"haskell_state.json";
+const PRELOADED_PATH: &CStr = c"src/Preloaded.hs";
 
-/// get haskell module name
-fn module_name(src: &str) -> Option<&str> {
-    let mut s = src.trim_start();
-    while s.starts_with("{-") {
-        let (_, t) = s.split_once("-}")?;
-        s = t.trim_start();
+mod header;
+
+use header::Header;
+pub(crate) use header::{parse as parse_header, skip_comments as skip_header_comments};
+
+fn extension(header: &Header) -> &'static str {
+    if header.literate {
+        "lhs"
+    } else {
+        "hs"
     }
-    while s.starts_with("-- ") {
-        let (_, t) = s.split_once('\n')?;
-        s = t.trim_start();
+}
+
+/// packages other than `base` and `hspec` providing imported modules, each prefixed by `sep`
+fn depends(header: &Header, sep: &str) -> String {
+    let mut packages = header
+        .imports
+        .iter()
+        .filter_map(|m| header::module_package(m))
+        .filter(|p| *p != "hspec")
+        .collect::<Vec<_>>();
+    packages.sort_unstable();
+    packages.dedup();
+    packages.iter().map(|p| format!("{sep}{p}")).collect()
+}
+
+/// `LANGUAGE` pragmas of the source enabled for the whole component
+fn default_extensions(header: &Header) -> String {
+    if header.extensions.is_empty() {
+        String::new()
+    } else {
+        format!("\n    default-extensions: {}", header.extensions.join(", "))
     }
-    Some(
-        s.strip_prefix("module")?
-            .trim_start()
-            .split_once(char::is_whitespace)?
-            .0,
-    )
 }
 
 #[derive(Serialize, Deserialize)]
@@ -86,39 +104,42 @@ impl Haskell {
             root,
         })
     }
-    pub fn create(root: &Path, project: Config) -> Result<Self, CreateError> {
-        fn write_code(
-            root: BorrowedFd,
-            base: String,
-            module: &str,
-            code: &str,
-        ) -> Result<CString, io::Error> {
-            let mut rel_path = base;
-            for m in module.split('.') {
-                rel_path.push('/');
-                rel_path.push_str(m);
-            }
-            rel_path.push_str(".hs");
-
-            fs::mkdir_all_at(root, Path::new(rel_path.as_str()).parent().unwrap())?;
-            let path = CString::new(rel_path)
-                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
-            fs::write(root, &path, code)?;
-            Ok(path)
-        }
+    pub fn create(
+        root: &Path,
+        project: Config,
+    ) -> Result<(Self, Option<SubstitutedToolchain>), CreateError> {
+        let (toolchain, substituted) = toolchain(KnownLangId::Haskell, project.version_id);
         let root = fs::open_dirfd(root).map_err(CreateErrorInner::Io)?;
 
-        let code_mod = module_name(project.code)
+        let code_header = header::parse(project.code);
+        let code_mod = code_header
+            .module
+            .as_deref()
             .ok_or_else(|| CreateErrorInner::UnknownCode(project.code.to_string()))?;
-        let code_path = write_code(root.as_fd(), "src".into(), code_mod, project.code)
-            .map_err(CreateErrorInner::WriteCode)?;
+        let code_path = write_module(
+            root.as_fd(),
+            "src",
+            code_mod,
+            extension(&code_header),
+            project.code,
+        )
+        .map_err(CreateErrorInner::WriteCode)?;
+
+        if project.has_preload {
+            fs::write(root.as_fd(), PRELOADED_PATH, "module Preloaded where\n")
+                .map_err(CreateErrorInner::Io)?;
+        }
 
-        let test_mod = module_name(project.fixture)
+        let test_header = header::parse(project.fixture);
+        let test_mod = test_header
+            .module
+            .as_deref()
             .ok_or_else(|| CreateErrorInner::UnknownTest(project.fixture.to_string()))?;
-        let test_path = write_code(
+        let test_path = write_module(
             root.as_fd(),
-            "test/sample".into(),
+            "test/sample",
             test_mod,
+            extension(&test_header),
             project.fixture,
         )
         .map_err(CreateErrorInner::WriteCode)?;
@@ -151,6 +172,15 @@ impl Haskell {
                 include_str!("./haskell/challenge.cabal"),
                 package = project.slug,
                 code_module = code_mod,
+                code_depends = depends(&code_header, ", "),
+                code_extensions = default_extensions(&code_header),
+                test_depends = depends(&test_header, ",\n        "),
+                test_extensions = default_extensions(&test_header),
+                preloaded_module = if project.has_preload {
+                    "\n                      Preloaded"
+                } else {
+                    ""
+                },
                 test_module = test_mod
             ),
         )
@@ -159,11 +189,14 @@ impl Haskell {
         fs::write(
             root.as_fd(),
             c"cabal.project.local",
-            include_str!("./haskell/cabal.project.local.in"),
+            format!(
+                include_str!("./haskell/cabal.project.local.in"),
+                ghc_version = toolchain.version
+            ),
         )
         .map_err(CreateErrorInner::Io)?;
 
-        Ok(Self { root, state })
+        Ok((Self { root, state }, substituted))
     }
 }
 impl WorkspaceObject for Haskell {
@@ -174,7 +207,8 @@ impl WorkspaceObject for Haskell {
         })
     }
     fn clean_build(&self) -> Result<(), io::Error> {
-        call_command_at(self.root.as_fd(), "cabal", ["clean"])
+        call_command_at(self.root.as_fd(), "cabal", ["clean"], build_timeout())?.check()?;
+        Ok(())
     }
     fn clean_session(&self) -> Result<(), io::Error> {
         fs::remove_at(self.root.as_fd(), STATE_FILE)?;
@@ -183,44 +217,3 @@ impl WorkspaceObject for Haskell {
         Ok(())
     }
 }
-
-#[cfg(test)]
-mod test {
-    mod mod_name {
-        use super::super::module_name;
-
-        fn test_module(code: &str, exp: &str) {
-            assert_eq!(module_name(code).unwrap(), exp);
-        }
-
-        #[test]
-        fn simple() {
-            test_module("module M1.M2.M3 where", "M1.M2.M3");
-            test_module("module M1.M2.M3 (f1, f2, f3) where", "M1.M2.M3");
-        }
-
-        #[test]
-        fn multiline_name() {
-            test_module("module M1.M2\n    ( f1, f2, f3) where", "M1.M2");
-        }
-
-        #[test]
-        fn extension() {
-            test_module(
-                "{-# LANGUAGE LambdaCase #-}\n\
-                {-# LANGUAGE TupleSections #-}\n\
-                module M1.M2 where",
-                "M1.M2",
-            )
-        }
-
-        #[test]
-        fn comment() {
-            test_module(
-                "-- comment\n\
-                module M1.M2 where",
-                "M1.M2",
-            )
-        }
-    }
-}
@@ -0,0 +1,363 @@
+//! Minimal parser for the header of haskell source: pragmas, module name and imports.
+
+const SYMBOL: &str = "!#$%&*+./<=>?@\\^|-~:";
+
+fn is_symbol(c: char) -> bool {
+    SYMBOL.contains(c)
+}
+
+/// `--` at `s` starts a line comment, not an operator like `-->`
+fn is_line_comment(prev: Option<char>, s: &str) -> bool {
+    if prev.is_some_and(is_symbol) {
+        return false;
+    }
+    let rest = s.trim_start_matches('-');
+    s.len() - rest.len() >= 2 && !rest.starts_with(is_symbol)
+}
+
+/// byte length of nested block comment starting at `s`, `None` if unterminated
+fn block_comment_len(s: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut idx = 0;
+    while idx < s.len() {
+        let t = &s[idx..];
+        if t.starts_with("{-") {
+            depth += 1;
+            idx += 2;
+        } else if t.starts_with("-}") {
+            depth -= 1;
+            idx += 2;
+            if depth == 0 {
+                return Some(idx);
+            }
+        } else {
+            idx += t.chars().next().unwrap().len_utf8();
+        }
+    }
+    None
+}
+
+/// Replace comments with spaces, keeping line breaks, and collect pragma contents
+fn strip_comments(src: &str) -> (String, Vec<&str>) {
+    fn blank(out: &mut String, s: &str) {
+        out.extend(s.chars().map(|c| if c == '\n' { '\n' } else { ' ' }));
+    }
+
+    let mut out = String::with_capacity(src.len());
+    let mut pragmas = Vec::new();
+    let mut prev = None;
+    let mut idx = 0;
+    while idx < src.len() {
+        let t = &src[idx..];
+        let len = if t.starts_with("{-") {
+            let len = block_comment_len(t).unwrap_or(t.len());
+            if let Some(p) = t[..len]
+                .strip_prefix("{-#")
+                .and_then(|p| p.strip_suffix("#-}"))
+            {
+                pragmas.push(p.trim());
+            }
+            blank(&mut out, &t[..len]);
+            len
+        } else if t.starts_with("--") && is_line_comment(prev, t) {
+            let len = t.find('\n').unwrap_or(t.len());
+            blank(&mut out, &t[..len]);
+            len
+        } else {
+            let c = t.chars().next().unwrap();
+            out.push(c);
+            c.len_utf8()
+        };
+        prev = src[..idx + len].chars().next_back();
+        idx += len;
+    }
+    (out, pragmas)
+}
+
+/// Extract code from literate haskell, `None` if `src` is not literate
+pub(crate) fn unlit(src: &str) -> Option<String> {
+    if src.lines().any(|l| l.trim_end() == "\\begin{code}") {
+        let mut in_code = false;
+        let mut ret = String::with_capacity(src.len());
+        for line in src.lines() {
+            match line.trim_end() {
+                "\\begin{code}" => in_code = true,
+                "\\end{code}" => in_code = false,
+                l if in_code => ret.push_str(l),
+                _ => (),
+            }
+            ret.push('\n');
+        }
+        return Some(ret);
+    }
+
+    let bird = src.lines().any(|l| l == ">" || l.starts_with("> "));
+    let plain = src
+        .lines()
+        .any(|l| l.starts_with("module ") || l.starts_with("import "));
+    if !bird || plain {
+        return None;
+    }
+    let mut ret = String::with_capacity(src.len());
+    for line in src.lines() {
+        if let Some(l) = line.strip_prefix('>') {
+            ret.push(' ');
+            ret.push_str(l);
+        }
+        ret.push('\n');
+    }
+    Some(ret)
+}
+
+fn name_token(s: &str) -> &str {
+    let end = s
+        .find(|c: char| !(c.is_alphanumeric() || matches!(c, '.' | '_' | '\'')))
+        .unwrap_or(s.len());
+    &s[..end]
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct Header {
+    pub module: Option<String>,
+    /// extensions enabled by `LANGUAGE` pragmas
+    pub extensions: Vec<String>,
+    pub imports: Vec<String>,
+    pub literate: bool,
+}
+
+/// Skip comments and pragmas before module header
+pub(crate) fn skip_comments(src: &str) -> Option<&str> {
+    let mut s = src.trim_start();
+    loop {
+        if s.starts_with("{-") {
+            s = s[block_comment_len(s)?..].trim_start();
+        } else if s.starts_with("--") && is_line_comment(None, s) {
+            s = s.split_once('\n')?.1.trim_start();
+        } else {
+            return Some(s);
+        }
+    }
+}
+
+/// Parse header of plain or literate haskell source
+pub(crate) fn parse(src: &str) -> Header {
+    let lit = unlit(src);
+    let (code, pragmas) = strip_comments(lit.as_deref().unwrap_or(src));
+
+    let mut header = Header {
+        literate: lit.is_some(),
+        ..Header::default()
+    };
+    for p in pragmas {
+        let Some((kind, rest)) = p.split_once(char::is_whitespace) else {
+            continue;
+        };
+        if kind.eq_ignore_ascii_case("LANGUAGE") {
+            header.extensions.extend(
+                rest.split(',')
+                    .map(str::trim)
+                    .filter(|e| !e.is_empty())
+                    .map(str::to_string),
+            );
+        }
+    }
+
+    let body = code.trim_start();
+    if let Some(rest) = body
+        .strip_prefix("module")
+        .filter(|r| r.starts_with(char::is_whitespace))
+    {
+        let name = name_token(rest.trim_start());
+        if !name.is_empty() {
+            header.module = Some(name.to_string());
+        }
+    }
+
+    // imports start at column of top level layout, which unlit may shift
+    let indent = code
+        .lines()
+        .find(|l| !l.trim().is_empty())
+        .map_or(0, |l| l.len() - l.trim_start().len());
+    for line in code.lines() {
+        let Some(rest) = line
+            .get(indent..)
+            .filter(|l| !l.starts_with(char::is_whitespace))
+            .and_then(|l| l.strip_prefix("import"))
+            .filter(|r| r.starts_with(char::is_whitespace))
+        else {
+            continue;
+        };
+        let mut rest = rest.trim_start();
+        loop {
+            if let Some(r) = ["safe", "qualified"]
+                .iter()
+                .find_map(|k| rest.strip_prefix(k))
+                .filter(|r| r.starts_with(char::is_whitespace))
+            {
+                rest = r.trim_start();
+            } else if let Some((_, r)) = rest.strip_prefix('"').and_then(|r| r.split_once('"')) {
+                // package import
+                rest = r.trim_start();
+            } else {
+                break;
+            }
+        }
+        let name = name_token(rest);
+        if !name.is_empty() {
+            header.imports.push(name.to_string());
+        }
+    }
+    header
+}
+
+/// Package providing module, `None` for modules of `base` or unknown packages
+pub(crate) fn module_package(module: &str) -> Option<&'static str> {
+    const PACKAGES: &[(&str, &str)] = &[
+        ("Test.QuickCheck", "QuickCheck"),
+        ("Test.Hspec", "hspec"),
+        ("Data.Map", "containers"),
+        ("Data.Set", "containers"),
+        ("Data.IntMap", "containers"),
+        ("Data.IntSet", "containers"),
+        ("Data.Sequence", "containers"),
+        ("Data.Tree", "containers"),
+        ("Data.Graph", "containers"),
+        ("Data.Text", "text"),
+        ("Data.ByteString", "bytestring"),
+        ("Data.Array", "array"),
+        ("Data.Vector", "vector"),
+        ("Data.HashMap", "unordered-containers"),
+        ("Data.HashSet", "unordered-containers"),
+        ("Data.Hashable", "hashable"),
+        ("Data.List.Split", "split"),
+        ("Control.Monad.State", "mtl"),
+        ("Control.Monad.Reader", "mtl"),
+        ("Control.Monad.Writer", "mtl"),
+        ("Control.Monad.Except", "mtl"),
+        ("Control.Monad.RWS", "mtl"),
+        ("Control.Monad.Trans", "transformers"),
+        ("Control.Monad.Identity", "mtl"),
+        ("Control.DeepSeq", "deepseq"),
+        ("Control.Parallel", "parallel"),
+        ("Text.Parsec", "parsec"),
+        ("Text.Megaparsec", "megaparsec"),
+        ("Text.Regex.TDFA", "regex-tdfa"),
+        ("Text.Printf", "base"),
+        ("System.Random", "random"),
+        ("Data.Time", "time"),
+        ("Data.Scientific", "scientific"),
+        ("Control.Lens", "lens"),
+    ];
+    PACKAGES
+        .iter()
+        .find(|(m, _)| {
+            module
+                .strip_prefix(m)
+                .is_some_and(|r| r.is_empty() || r.starts_with('.'))
+        })
+        .map(|(_, p)| *p)
+        .filter(|p| *p != "base")
+}
+
+#[cfg(test)]
+mod test {
+    use super::{module_package, parse, Header};
+
+    fn module(src: &str) -> Option<String> {
+        parse(src).module
+    }
+
+    #[test]
+    fn comments() {
+        assert_eq!(
+            module("--comment without space\n---- more\nmodule A.B where").as_deref(),
+            Some("A.B")
+        );
+        assert_eq!(
+            module("{- outer {- inner -} still comment -}\nmodule Nested (f) where").as_deref(),
+            Some("Nested")
+        );
+        assert_eq!(module("-->\nmodule M where"), None);
+    }
+
+    #[test]
+    fn module_name() {
+        assert_eq!(module("module M1.M2.M3 where").as_deref(), Some("M1.M2.M3"));
+        assert_eq!(module("module Kata(f) where").as_deref(), Some("Kata"));
+        assert_eq!(
+            module("module M1.M2\n    ( f1, f2, f3) where").as_deref(),
+            Some("M1.M2")
+        );
+        // idris modules need no `where`
+        assert_eq!(module("module Kata").as_deref(), Some("Kata"));
+        assert_eq!(module("module "), None);
+    }
+
+    #[test]
+    fn pragmas() {
+        let header = parse(
+            "{-# LANGUAGE LambdaCase, TupleSections #-}\n\
+            {-# language BangPatterns #-}\n\
+            {-# OPTIONS_GHC -Wall #-}\n\
+            module Kata where\n\
+            \n\
+            import qualified Data.Map.Strict as M\n\
+            import Data.List (sort)\n\
+            import safe \"text\" Data.Text (Text)\n\
+            -- import Commented.Out\n\
+            {- import Block.Comment -}\n",
+        );
+        assert_eq!(
+            header,
+            Header {
+                module: Some("Kata".to_string()),
+                extensions: vec![
+                    "LambdaCase".to_string(),
+                    "TupleSections".to_string(),
+                    "BangPatterns".to_string()
+                ],
+                imports: vec![
+                    "Data.Map.Strict".to_string(),
+                    "Data.List".to_string(),
+                    "Data.Text".to_string()
+                ],
+                literate: false,
+            }
+        );
+    }
+
+    #[test]
+    fn literate() {
+        let bird = parse(
+            "Solution of the kata.\n\
+            \n\
+            > module Bird where\n\
+            > import Data.Char\n\
+            \n\
+            > f = id\n",
+        );
+        assert!(bird.literate);
+        assert_eq!(bird.module.as_deref(), Some("Bird"));
+        assert_eq!(bird.imports, ["Data.Char"]);
+
+        let latex = parse(
+            "\\section{Solution}\n\
+            \\begin{code}\n\
+            module Latex where\n\
+            import Test.QuickCheck\n\
+            \\end{code}\n",
+        );
+        assert!(latex.literate);
+        assert_eq!(latex.module.as_deref(), Some("Latex"));
+        assert_eq!(latex.imports, ["Test.QuickCheck"]);
+    }
+
+    #[test]
+    fn packages() {
+        assert_eq!(module_package("Test.QuickCheck"), Some("QuickCheck"));
+        assert_eq!(module_package("Data.Map.Strict"), Some("containers"));
+        assert_eq!(module_package("Data.Maybe"), None);
+        assert_eq!(module_package("Data.Settings"), None);
+        assert_eq!(module_package("Text.Printf"), None);
+    }
+}
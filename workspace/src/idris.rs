@@ -0,0 +1,149 @@
+use std::{
+    ffi::{CStr, CString},
+    io,
+    path::Path,
+};
+
+use rustix::{
+    fd::{AsFd, OwnedFd},
+    io::Errno,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    haskell::parse_header,
+    util::{build_timeout, call_command_at, fs, write_module},
+    Code, Config, WorkspaceObject,
+};
+
+const STATE_FILE: &CStr = c"idris_state.json";
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    code_path: CString,
+    fixture_path: CString,
+    ipkg: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+enum OpenErrInner {
+    #[error("failed to open state")]
+    Io(#[source] Errno),
+    #[error("failed to deserialize json")]
+    Json(#[source] serde_json::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct OpenError(#[from] OpenErrInner);
+
+#[derive(Debug, thiserror::Error)]
+enum CreateErrorInner {
+    #[error("unknown code {0}")]
+    UnknownCode(String),
+    #[error("unknown test {0}")]
+    UnknownTest(String),
+    #[error("failed to write source code")]
+    WriteCode(#[source] io::Error),
+    #[error("io error")]
+    Io(#[source] Errno),
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct CreateError(#[from] CreateErrorInner);
+
+pub struct Idris {
+    root: OwnedFd,
+    state: State,
+}
+
+impl Idris {
+    pub fn open(root: &Path) -> Result<Self, OpenError> {
+        let root = fs::open_dirfd(root).map_err(OpenErrInner::Io)?;
+        Ok(Self {
+            state: serde_json::from_slice(
+                &fs::read(root.as_fd(), STATE_FILE).map_err(OpenErrInner::Io)?,
+            )
+            .map_err(OpenErrInner::Json)?,
+            root,
+        })
+    }
+    pub fn create(root: &Path, project: Config) -> Result<Self, CreateError> {
+        let root = fs::open_dirfd(root).map_err(CreateErrorInner::Io)?;
+
+        let code_mod = parse_header(project.code)
+            .module
+            .ok_or_else(|| CreateErrorInner::UnknownCode(project.code.to_string()))?;
+        let code_path = write_module(root.as_fd(), "src", &code_mod, "idr", project.code)
+            .map_err(CreateErrorInner::WriteCode)?;
+
+        let test_mod = parse_header(project.fixture)
+            .module
+            .ok_or_else(|| CreateErrorInner::UnknownTest(project.fixture.to_string()))?;
+        let fixture_path = write_module(root.as_fd(), "src", &test_mod, "idr", project.fixture)
+            .map_err(CreateErrorInner::WriteCode)?;
+
+        let modules = if project.has_preload {
+            fs::write(
+                root.as_fd(),
+                c"src/Preloaded.idr",
+                // codewars runs idris 1 with specdris, like the ipkg and `idris --clean`
+                "module Preloaded\n\n%access export\n",
+            )
+            .map_err(CreateErrorInner::Io)?;
+            format!("Preloaded, {code_mod}")
+        } else {
+            code_mod.to_string()
+        };
+
+        let ipkg = format!("{}.ipkg", project.slug);
+        fs::write(
+            root.as_fd(),
+            ipkg.as_str(),
+            format!(
+                include_str!("./idris/challenge.ipkg"),
+                package = project.slug.replace('-', "_"),
+                modules = modules,
+                test_module = test_mod
+            ),
+        )
+        .map_err(CreateErrorInner::Io)?;
+
+        let state = State {
+            code_path,
+            fixture_path,
+            ipkg,
+        };
+        fs::write(
+            root.as_fd(),
+            STATE_FILE,
+            serde_json::to_vec(&state).unwrap(),
+        )
+        .map_err(CreateErrorInner::Io)?;
+
+        Ok(Self { root, state })
+    }
+}
+impl WorkspaceObject for Idris {
+    fn get_code(&self) -> Result<Code, io::Error> {
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), &self.state.code_path)?,
+            fixture: fs::read_to_string(self.root.as_fd(), &self.state.fixture_path)?,
+        })
+    }
+    fn clean_build(&self) -> Result<(), io::Error> {
+        call_command_at(
+            self.root.as_fd(),
+            "idris",
+            ["--clean", &self.state.ipkg],
+            build_timeout(),
+        )?
+        .check()?;
+        Ok(())
+    }
+    fn clean_session(&self) -> Result<(), io::Error> {
+        fs::remove_at(self.root.as_fd(), STATE_FILE)?;
+        Ok(())
+    }
+}
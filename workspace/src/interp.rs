@@ -0,0 +1,5 @@
+//! Local interpreters for languages that only run on Codewars specific runners.
+
+pub mod bf;
+pub mod cases;
+pub mod lc;
@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+
+/// Value stored in current cell when `,` reads past end of input
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Eof {
+    /// set cell to 0, like the Codewars runner
+    #[default]
+    Zero,
+    /// keep cell value
+    Unchanged,
+    /// set cell to 255
+    MinusOne,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    #[serde(default)]
+    pub eof: Eof,
+    #[serde(default = "default_max_steps")]
+    pub max_steps: u64,
+}
+fn default_max_steps() -> u64 {
+    100_000_000
+}
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            eof: Eof::default(),
+            max_steps: default_max_steps(),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("unmatched `[` at {0}")]
+    UnmatchedOpen(usize),
+    #[error("unmatched `]` at {0}")]
+    UnmatchedClose(usize),
+    #[error("pointer moved before first cell at {0}")]
+    NegativePointer(usize),
+    #[error("step limit {0} exceeded")]
+    StepLimit(u64),
+}
+
+/// Run program on input and return its output.
+///
+/// Cells are wrapping bytes on a tape that grows to the right, other characters are comments.
+pub fn run(code: &str, input: &[u8], config: &Config) -> Result<Vec<u8>, Error> {
+    let code = code.as_bytes();
+
+    let mut jump = vec![0; code.len()];
+    let mut open = Vec::new();
+    for (idx, c) in code.iter().enumerate() {
+        match c {
+            b'[' => open.push(idx),
+            b']' => {
+                let start = open.pop().ok_or(Error::UnmatchedClose(idx))?;
+                jump[start] = idx;
+                jump[idx] = start;
+            }
+            _ => (),
+        }
+    }
+    if let Some(idx) = open.pop() {
+        return Err(Error::UnmatchedOpen(idx));
+    }
+
+    let mut tape = vec![0u8; 1];
+    let mut ptr = 0;
+    let mut input = input.iter();
+    let mut output = Vec::new();
+    let mut pc = 0;
+    let mut steps = 0;
+    while pc < code.len() {
+        match code[pc] {
+            b'+' => tape[ptr] = tape[ptr].wrapping_add(1),
+            b'-' => tape[ptr] = tape[ptr].wrapping_sub(1),
+            b'>' => {
+                ptr += 1;
+                if ptr == tape.len() {
+                    tape.push(0);
+                }
+            }
+            b'<' => ptr = ptr.checked_sub(1).ok_or(Error::NegativePointer(pc))?,
+            b'.' => output.push(tape[ptr]),
+            b',' => match input.next() {
+                Some(b) => tape[ptr] = *b,
+                None => match config.eof {
+                    Eof::Zero => tape[ptr] = 0,
+                    Eof::Unchanged => (),
+                    Eof::MinusOne => tape[ptr] = 255,
+                },
+            },
+            b'[' if tape[ptr] == 0 => pc = jump[pc],
+            b']' if tape[ptr] != 0 => pc = jump[pc],
+            _ => {
+                pc += 1;
+                continue;
+            }
+        }
+        steps += 1;
+        if steps > config.max_steps {
+            return Err(Error::StepLimit(config.max_steps));
+        }
+        pc += 1;
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{run, Config, Eof, Error};
+
+    #[test]
+    fn hello() {
+        let code = "++++++++[>++++[>++>+++>+++>+<<<<-]>+>+>->>+[<]<-]>>.>---.+++++++..+++.";
+        assert_eq!(run(code, b"", &Config::default()).unwrap(), b"Hello");
+    }
+
+    #[test]
+    fn echo_eof() {
+        let code = ",[.,]";
+        assert_eq!(run(code, b"abc", &Config::default()).unwrap(), b"abc");
+
+        let cfg = Config {
+            eof: Eof::MinusOne,
+            ..Config::default()
+        };
+        assert_eq!(run(",+.", b"", &cfg).unwrap(), [0]);
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            run("[", b"", &Config::default()),
+            Err(Error::UnmatchedOpen(0))
+        ));
+        assert!(matches!(
+            run("<", b"", &Config::default()),
+            Err(Error::NegativePointer(0))
+        ));
+        let cfg = Config {
+            max_steps: 100,
+            ..Config::default()
+        };
+        assert!(matches!(run("+[]", b"", &cfg), Err(Error::StepLimit(100))));
+    }
+}
@@ -0,0 +1,271 @@
+//! Test cases extracted from JavaScript sample fixtures of interpreted languages.
+//!
+//! Only assertions comparing a call with literal arguments to a literal are understood,
+//! other statements of the fixture are ignored and other assertions reported as skipped.
+
+use std::{fmt, io};
+
+/// `assert.<method>(args...)` call with source of its arguments
+#[derive(Debug, PartialEq, Eq)]
+pub struct Assertion<'a> {
+    pub method: &'a str,
+    pub args: Vec<&'a str>,
+}
+
+impl fmt::Display for Assertion<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "assert.{}({})", self.method, self.args.join(", "))
+    }
+}
+
+/// Test cases of fixture and source of assertions which could not be turned into one
+#[derive(Debug, PartialEq, Eq)]
+pub struct Cases<T> {
+    pub cases: Vec<T>,
+    pub skipped: Vec<String>,
+}
+
+fn is_ident(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+/// end of string literal starting at `s`, skipping escaped characters
+fn literal_len(s: &str) -> usize {
+    let quote = s.chars().next().unwrap();
+    let mut chars = s.char_indices().skip(1);
+    while let Some((idx, c)) = chars.next() {
+        if c == '\\' {
+            chars.next();
+        } else if c == quote {
+            return idx + 1;
+        }
+    }
+    s.len()
+}
+
+/// length of balanced argument list starting after `(` and the arguments in it
+fn arguments(s: &str) -> Option<(usize, Vec<&str>)> {
+    let mut args = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    let mut idx = 0;
+    while let Some(c) = s[idx..].chars().next() {
+        match c {
+            '"' | '\'' | '`' => {
+                idx += literal_len(&s[idx..]);
+                continue;
+            }
+            '(' | '[' | '{' => depth += 1,
+            ')' if depth == 0 => {
+                let last = s[start..idx].trim();
+                if !last.is_empty() {
+                    args.push(last);
+                }
+                return Some((idx + 1, args));
+            }
+            ')' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                args.push(s[start..idx].trim());
+                start = idx + 1;
+            }
+            _ => (),
+        }
+        idx += c.len_utf8();
+    }
+    None
+}
+
+/// Assertions of fixture in source order
+pub fn assertions(src: &str) -> Vec<Assertion<'_>> {
+    let mut ret = Vec::new();
+    let mut idx = 0;
+    while let Some(pos) = src[idx..].find("assert.") {
+        let start = idx + pos;
+        idx = start + "assert.".len();
+        if src[..start].ends_with(is_ident) || src[..start].ends_with('.') {
+            continue;
+        }
+        let rest = &src[idx..];
+        let len = rest.find(|c| !is_ident(c)).unwrap_or(rest.len());
+        let method = &rest[..len];
+        let Some(call) = rest[len..].trim_start().strip_prefix('(') else {
+            continue;
+        };
+        if let Some((end, args)) = arguments(call) {
+            idx = src.len() - call.len() + end;
+            ret.push(Assertion { method, args });
+        }
+    }
+    ret
+}
+
+/// Test cases built by `case` from each assertion of fixture
+pub fn extract<T>(src: &str, case: impl Fn(&Assertion) -> Option<T>) -> Cases<T> {
+    let mut ret = Cases {
+        cases: Vec::new(),
+        skipped: Vec::new(),
+    };
+    for a in assertions(src) {
+        match case(&a) {
+            Some(c) => ret.cases.push(c),
+            None => ret.skipped.push(a.to_string()),
+        }
+    }
+    ret
+}
+
+/// Value of JavaScript string literal, `None` if `src` is not a single literal
+pub fn string(src: &str) -> Option<String> {
+    let quote = src
+        .chars()
+        .next()
+        .filter(|c| matches!(c, '"' | '\'' | '`'))?;
+    if literal_len(src) != src.len() || !src[1..].ends_with(quote) {
+        return None;
+    }
+    let mut ret = String::new();
+    let mut chars = src[1..src.len() - 1].chars();
+    while let Some(c) = chars.next() {
+        if quote == '`' && c == '$' {
+            // template substitutions need evaluation
+            return None;
+        }
+        if c != '\\' {
+            ret.push(c);
+            continue;
+        }
+        let hex = |chars: &mut std::str::Chars, n| {
+            let digits: String = chars.take(n).collect();
+            u32::from_str_radix(&digits, 16)
+                .ok()
+                .and_then(char::from_u32)
+        };
+        ret.push(match chars.next()? {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            'x' => hex(&mut chars, 2)?,
+            'u' => hex(&mut chars, 4)?,
+            c => c,
+        });
+    }
+    Some(ret)
+}
+
+/// Callee and argument lists of call like `solution.f(1, x)(2)`
+///
+/// Only the last component of a dotted callee is returned.
+pub fn call(src: &str) -> Option<(&str, Vec<Vec<&str>>)> {
+    let len = src
+        .find(|c| !(is_ident(c) || c == '.'))
+        .unwrap_or(src.len());
+    let name = src[..len].rsplit('.').next()?;
+    if name.is_empty() {
+        return None;
+    }
+    let mut calls = Vec::new();
+    let mut rest = src[len..].trim_start();
+    while !rest.is_empty() {
+        let (end, args) = arguments(rest.strip_prefix('(')?)?;
+        calls.push(args);
+        rest = rest[1 + end..].trim_start();
+    }
+    (!calls.is_empty()).then_some((name, calls))
+}
+
+/// Value of non negative JavaScript integer literal
+pub fn number(src: &str) -> Option<u64> {
+    if !src.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    src.replace('_', "").parse().ok()
+}
+
+/// Print outcome of each `(case, failure)` and skipped assertions,
+/// and fail unless all of at least one case passed
+pub fn report(outcomes: &[(String, Option<String>)], skipped: &[String]) -> io::Result<()> {
+    for assertion in skipped {
+        println!("SKIPPED: {assertion}");
+    }
+    let mut failed = 0;
+    for (case, failure) in outcomes {
+        match failure {
+            None => println!("ok: {case}"),
+            Some(msg) => {
+                failed += 1;
+                println!("FAILED: {case}\n  {msg}");
+            }
+        }
+    }
+    if outcomes.is_empty() {
+        Err(io::Error::other(format!(
+            "no supported assertion found in fixture, {} skipped",
+            skipped.len()
+        )))
+    } else if failed != 0 {
+        Err(io::Error::other(format!(
+            "{failed} of {} cases failed",
+            outcomes.len()
+        )))
+    } else {
+        if !skipped.is_empty() {
+            println!(
+                "{} unsupported assertions skipped, check them on codewars",
+                skipped.len()
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{assertions, call, number, string, Assertion};
+
+    #[test]
+    fn extract() {
+        let src = r#"const { assert } = require("chai");
+describe("kata", () => {
+  it("works", () => {
+    assert.strictEqual(runBF("a,b)"), "b(a", "message, with comma");
+    assert.numEqual(solution.add(f(1), 2), 3);
+    notassert.equal(x, y);
+  });
+});"#;
+        assert_eq!(
+            assertions(src),
+            [
+                Assertion {
+                    method: "strictEqual",
+                    args: vec![r#"runBF("a,b)")"#, r#""b(a""#, r#""message, with comma""#],
+                },
+                Assertion {
+                    method: "numEqual",
+                    args: vec!["solution.add(f(1), 2)", "3"],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn literals() {
+        assert_eq!(string(r#""a\n\x41B\"""#).as_deref(), Some("a\nAB\""));
+        assert_eq!(string("'it\\'s'").as_deref(), Some("it's"));
+        assert_eq!(string(r#""a" + "b""#), None);
+        assert_eq!(string("`${x}`"), None);
+        assert_eq!(number("1_000"), Some(1000));
+        assert_eq!(number("x1"), None);
+    }
+
+    #[test]
+    fn calls() {
+        assert_eq!(
+            call("solution.add(1, x)(g(2))"),
+            Some(("add", vec![vec!["1", "x"], vec!["g(2)"]]))
+        );
+        assert_eq!(call(r#"runBF("")"#), Some(("runBF", vec![vec![r#""""#]])));
+        assert_eq!(call("add"), None);
+        assert_eq!(call("add(1) + 2"), None);
+    }
+}
@@ -0,0 +1,759 @@
+use std::{collections::HashMap, fmt, rc::Rc};
+
+use serde::{Deserialize, Serialize};
+
+/// Which top level definitions a definition may refer to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Purity {
+    /// only earlier definitions
+    Let,
+    /// any definition, including itself
+    #[default]
+    LetRec,
+    /// no definitions at all
+    PureLC,
+}
+
+/// Encoding of number literals
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NumEncoding {
+    /// number literals are not allowed
+    #[default]
+    None,
+    Church,
+    Scott,
+    BinaryScott,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    #[serde(default)]
+    pub purity: Purity,
+    #[serde(default)]
+    pub num_encoding: NumEncoding,
+    #[serde(default = "default_max_steps")]
+    pub max_steps: u64,
+}
+fn default_max_steps() -> u64 {
+    10_000_000
+}
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            purity: Purity::default(),
+            num_encoding: NumEncoding::default(),
+            max_steps: default_max_steps(),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("syntax error at line {line}: {msg}")]
+    Syntax { line: usize, msg: &'static str },
+    #[error("duplicate definition {0}")]
+    Duplicate(String),
+    #[error("undefined name {0}")]
+    Undefined(String),
+    #[error("{0} can not be referenced with purity {1:?}")]
+    Purity(String, Purity),
+    #[error("number literal {0} is not allowed without number encoding")]
+    Number(u64),
+    #[error("step limit {0} exceeded")]
+    StepLimit(u64),
+}
+
+#[derive(Debug)]
+enum Term {
+    /// de Bruijn index of bound variable
+    Var(usize),
+    /// top level definition
+    Global(usize),
+    /// free marker used when decoding values
+    Free(Marker),
+    Lam(Rc<Term>),
+    App(Rc<Term>, Rc<Term>),
+}
+type TermRef = Rc<Term>;
+
+impl Drop for Term {
+    /// free subterms iteratively, reduced terms may nest deeper than the native stack allows
+    fn drop(&mut self) {
+        fn take(t: &mut Term, stack: &mut Vec<TermRef>) {
+            // only the last reference frees its term, others just decrement
+            let mut take = |c: &mut TermRef| {
+                if Rc::get_mut(c).is_some() {
+                    stack.push(std::mem::replace(c, Rc::new(Term::Global(0))));
+                }
+            };
+            match t {
+                Term::Lam(b) => take(b),
+                Term::App(f, a) => {
+                    take(f);
+                    take(a);
+                }
+                Term::Var(_) | Term::Global(_) | Term::Free(_) => (),
+            }
+        }
+        let mut stack = Vec::new();
+        take(self, &mut stack);
+        while let Some(t) = stack.pop() {
+            if let Ok(mut t) = Rc::try_unwrap(t) {
+                take(&mut t, &mut stack);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Marker {
+    A,
+    B,
+    C,
+}
+
+fn lam(body: TermRef) -> TermRef {
+    Rc::new(Term::Lam(body))
+}
+fn app(f: TermRef, a: TermRef) -> TermRef {
+    Rc::new(Term::App(f, a))
+}
+fn var(idx: usize) -> TermRef {
+    Rc::new(Term::Var(idx))
+}
+
+/// Pending work of iterative term traversals
+enum Frame<T> {
+    Visit(T, usize),
+    Lam,
+    App,
+}
+
+/// combine rebuilt children on top of `done` as required by `frame`
+fn rebuild<T>(frame: Frame<T>, done: &mut Vec<TermRef>) {
+    let t = match frame {
+        Frame::Visit(..) => unreachable!(),
+        Frame::Lam => lam(done.pop().unwrap()),
+        Frame::App => {
+            let a = done.pop().unwrap();
+            app(done.pop().unwrap(), a)
+        }
+    };
+    done.push(t);
+}
+
+/// rebuild `t` replacing each bound variable by `f(var, index, binders above it)`
+///
+/// Uses explicit stack since reduced terms may nest deeper than the native stack allows.
+fn map_vars(t: &TermRef, f: impl Fn(&TermRef, usize, usize) -> TermRef) -> TermRef {
+    let mut work = vec![Frame::Visit(t, 0)];
+    let mut done = Vec::new();
+    while let Some(frame) = work.pop() {
+        match frame {
+            Frame::Visit(t, binders) => match &**t {
+                Term::Var(i) => done.push(f(t, *i, binders)),
+                Term::Global(_) | Term::Free(_) => done.push(t.clone()),
+                Term::Lam(b) => {
+                    work.push(Frame::Lam);
+                    work.push(Frame::Visit(b, binders + 1));
+                }
+                Term::App(g, a) => {
+                    work.push(Frame::App);
+                    work.push(Frame::Visit(a, binders));
+                    work.push(Frame::Visit(g, binders));
+                }
+            },
+            frame => rebuild(frame, &mut done),
+        }
+    }
+    done.pop().unwrap()
+}
+
+/// shift free variables of `t` not below `cutoff` by `d`
+fn shift(t: &TermRef, d: usize, cutoff: usize) -> TermRef {
+    map_vars(t, |v, i, binders| {
+        if i >= cutoff + binders {
+            var(i + d)
+        } else {
+            v.clone()
+        }
+    })
+}
+
+/// substitute variable `depth` in `t` with `arg`, removing the binder
+fn subst(t: &TermRef, arg: &TermRef, depth: usize) -> TermRef {
+    map_vars(t, |v, i, binders| {
+        let depth = depth + binders;
+        match i.cmp(&depth) {
+            std::cmp::Ordering::Equal => shift(arg, depth, 0),
+            std::cmp::Ordering::Greater => var(i - 1),
+            std::cmp::Ordering::Less => v.clone(),
+        }
+    })
+}
+
+fn encode(n: u64, encoding: NumEncoding) -> Option<TermRef> {
+    match encoding {
+        NumEncoding::None => None,
+        NumEncoding::Church => {
+            let mut body = var(0);
+            for _ in 0..n {
+                body = app(var(1), body);
+            }
+            Some(lam(lam(body)))
+        }
+        NumEncoding::Scott => {
+            let mut ret = lam(lam(var(1)));
+            for _ in 0..n {
+                ret = lam(lam(app(var(0), ret)));
+            }
+            Some(ret)
+        }
+        NumEncoding::BinaryScott => Some(if n == 0 {
+            lam(lam(lam(var(2))))
+        } else {
+            let rest = encode(n / 2, encoding)?;
+            // even numbers select second continuation, odd numbers the third
+            lam(lam(lam(app(var(1 - (n % 2) as usize), rest))))
+        }),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Ident(&'a str),
+    Number(u64),
+    Lambda,
+    Dot,
+    Open,
+    Close,
+}
+
+fn tokenize(src: &str, line: usize) -> Result<Vec<Token<'_>>, Error> {
+    let syntax = |msg| Error::Syntax { line, msg };
+    let mut ret = Vec::new();
+    let mut s = src;
+    loop {
+        s = s.trim_start();
+        let Some(c) = s.chars().next() else {
+            return Ok(ret);
+        };
+        let len = match c {
+            '\\' | 'λ' => {
+                ret.push(Token::Lambda);
+                c.len_utf8()
+            }
+            '.' => {
+                ret.push(Token::Dot);
+                1
+            }
+            '(' => {
+                ret.push(Token::Open);
+                1
+            }
+            ')' => {
+                ret.push(Token::Close);
+                1
+            }
+            c if c.is_ascii_digit() => {
+                let len = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+                ret.push(Token::Number(
+                    s[..len].parse().map_err(|_| syntax("number too large"))?,
+                ));
+                len
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let len = s
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '\'' || c == '-'))
+                    .unwrap_or(s.len());
+                ret.push(Token::Ident(&s[..len]));
+                len
+            }
+            _ => return Err(syntax("unexpected character")),
+        };
+        s = &s[len..];
+    }
+}
+
+struct Parser<'a, 'b> {
+    tokens: &'b [Token<'a>],
+    pos: usize,
+    line: usize,
+    /// bound variables, innermost last
+    scope: Vec<&'a str>,
+    /// resolve top level name
+    global: &'b dyn Fn(&str) -> Result<TermRef, Error>,
+    encoding: NumEncoding,
+}
+impl<'a, 'b> Parser<'a, 'b> {
+    fn error(&self, msg: &'static str) -> Error {
+        Error::Syntax {
+            line: self.line,
+            msg,
+        }
+    }
+    fn peek(&self) -> Option<&Token<'a>> {
+        self.tokens.get(self.pos)
+    }
+    fn term(&mut self) -> Result<TermRef, Error> {
+        if self.peek() == Some(&Token::Lambda) {
+            self.pos += 1;
+            let mut params = 0;
+            loop {
+                match self.peek() {
+                    Some(Token::Ident(n)) => {
+                        self.scope.push(n);
+                        params += 1;
+                        self.pos += 1;
+                    }
+                    Some(Token::Dot) if params > 0 => {
+                        self.pos += 1;
+                        break;
+                    }
+                    _ => return Err(self.error("expect parameter or `.`")),
+                }
+            }
+            let mut body = self.term()?;
+            for _ in 0..params {
+                self.scope.pop();
+                body = lam(body);
+            }
+            return Ok(body);
+        }
+
+        let mut ret = self.atom()?;
+        loop {
+            match self.peek() {
+                None | Some(Token::Close) => return Ok(ret),
+                // lambda extends as far right as possible
+                Some(Token::Lambda) => return Ok(app(ret, self.term()?)),
+                _ => ret = app(ret, self.atom()?),
+            }
+        }
+    }
+    fn atom(&mut self) -> Result<TermRef, Error> {
+        let tokens = self.tokens;
+        let tok = tokens
+            .get(self.pos)
+            .ok_or_else(|| self.error("unexpected end"))?;
+        self.pos += 1;
+        match tok {
+            Token::Ident(n) => match self.scope.iter().rev().position(|v| v == n) {
+                Some(idx) if *n != "_" => Ok(var(idx)),
+                _ => (self.global)(n),
+            },
+            Token::Number(n) => encode(*n, self.encoding).ok_or(Error::Number(*n)),
+            Token::Open => {
+                let t = self.term()?;
+                match self.peek() {
+                    Some(Token::Close) => {
+                        self.pos += 1;
+                        Ok(t)
+                    }
+                    _ => Err(self.error("expect `)`")),
+                }
+            }
+            _ => Err(self.error("unexpected token")),
+        }
+    }
+}
+
+fn parse(
+    src: &str,
+    line: usize,
+    global: &dyn Fn(&str) -> Result<TermRef, Error>,
+    encoding: NumEncoding,
+) -> Result<TermRef, Error> {
+    let tokens = tokenize(src, line)?;
+    let mut parser = Parser {
+        tokens: &tokens,
+        pos: 0,
+        line,
+        scope: Vec::new(),
+        global,
+        encoding,
+    };
+    let t = parser.term()?;
+    if parser.pos != tokens.len() {
+        return Err(parser.error("unexpected token"));
+    }
+    Ok(t)
+}
+
+/// Split source into `(line, name, body)` definitions.
+///
+/// A definition starts at an unindented line, indented lines continue it.
+fn definitions(src: &str) -> Result<Vec<(usize, &str, String)>, Error> {
+    let mut ret: Vec<(usize, &str, String)> = Vec::new();
+    for (idx, line) in src.lines().enumerate() {
+        let line = line.split_once('#').map_or(line, |(l, _)| l);
+        if line.trim().is_empty() {
+            continue;
+        }
+        if line.starts_with(char::is_whitespace) {
+            match ret.last_mut() {
+                Some((_, _, body)) => {
+                    body.push(' ');
+                    body.push_str(line);
+                }
+                None => {
+                    return Err(Error::Syntax {
+                        line: idx + 1,
+                        msg: "indented line without definition",
+                    })
+                }
+            }
+        } else {
+            let (name, body) = line.split_once('=').ok_or(Error::Syntax {
+                line: idx + 1,
+                msg: "expect `=`",
+            })?;
+            ret.push((idx + 1, name.trim(), body.to_string()));
+        }
+    }
+    Ok(ret)
+}
+
+struct Machine<'a> {
+    globals: &'a [TermRef],
+    steps: u64,
+    max_steps: u64,
+}
+impl Machine<'_> {
+    fn tick(&mut self) -> Result<(), Error> {
+        self.steps += 1;
+        if self.steps > self.max_steps {
+            Err(Error::StepLimit(self.max_steps))
+        } else {
+            Ok(())
+        }
+    }
+    /// reduce to weak head normal form in normal order
+    fn whnf(&mut self, mut t: TermRef) -> Result<TermRef, Error> {
+        let mut args = Vec::new();
+        loop {
+            match &*t {
+                Term::App(f, a) => {
+                    args.push(a.clone());
+                    t = f.clone();
+                }
+                Term::Global(idx) => {
+                    self.tick()?;
+                    t = self.globals[*idx].clone();
+                }
+                Term::Lam(b) if !args.is_empty() => {
+                    self.tick()?;
+                    t = subst(b, &args.pop().unwrap(), 0);
+                }
+                _ => break,
+            }
+        }
+        while let Some(a) = args.pop() {
+            t = app(t, a);
+        }
+        Ok(t)
+    }
+    /// reduce to normal form in normal order
+    fn normalize(&mut self, t: TermRef) -> Result<TermRef, Error> {
+        let mut work = vec![Frame::Visit(t, 0)];
+        let mut done = Vec::new();
+        while let Some(frame) = work.pop() {
+            match frame {
+                Frame::Visit(t, _) => {
+                    let t = self.whnf(t)?;
+                    match &*t {
+                        Term::Lam(b) => {
+                            work.push(Frame::Lam);
+                            work.push(Frame::Visit(b.clone(), 0));
+                        }
+                        Term::App(f, a) => {
+                            work.push(Frame::App);
+                            work.push(Frame::Visit(a.clone(), 0));
+                            work.push(Frame::Visit(f.clone(), 0));
+                        }
+                        _ => done.push(t),
+                    }
+                }
+                frame => rebuild(frame, &mut done),
+            }
+        }
+        Ok(done.pop().unwrap())
+    }
+    fn decode(&mut self, t: &TermRef, encoding: NumEncoding) -> Result<Option<u64>, Error> {
+        let a = Rc::new(Term::Free(Marker::A));
+        let b = Rc::new(Term::Free(Marker::B));
+        let c = Rc::new(Term::Free(Marker::C));
+        let is = |t: &TermRef, m: Marker| matches!(&**t, Term::Free(v) if *v == m);
+        match encoding {
+            NumEncoding::None => Ok(None),
+            NumEncoding::Church => {
+                let mut t = self.normalize(app(app(t.clone(), a), b))?;
+                let mut n = 0;
+                loop {
+                    match &*t {
+                        Term::Free(Marker::B) => return Ok(Some(n)),
+                        Term::App(f, x) if is(f, Marker::A) => {
+                            n += 1;
+                            t = x.clone();
+                        }
+                        _ => return Ok(None),
+                    }
+                }
+            }
+            NumEncoding::Scott => {
+                let mut t = t.clone();
+                let mut n = 0;
+                loop {
+                    let w = self.whnf(app(app(t, a.clone()), b.clone()))?;
+                    match &*w {
+                        Term::Free(Marker::A) => return Ok(Some(n)),
+                        Term::App(f, pred) if is(f, Marker::B) => {
+                            n += 1;
+                            t = pred.clone();
+                        }
+                        _ => return Ok(None),
+                    }
+                }
+            }
+            NumEncoding::BinaryScott => {
+                let mut t = t.clone();
+                let mut n = 0u64;
+                let mut bit = 0;
+                loop {
+                    let w = self.whnf(app(app(app(t, a.clone()), b.clone()), c.clone()))?;
+                    match &*w {
+                        Term::Free(Marker::A) => return Ok(Some(n)),
+                        Term::App(f, rest)
+                            if bit < 64 && (is(f, Marker::B) || is(f, Marker::C)) =>
+                        {
+                            if is(f, Marker::C) {
+                                n |= 1 << bit;
+                            }
+                            bit += 1;
+                            t = rest.clone();
+                        }
+                        _ => return Ok(None),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Result of evaluating an expression
+#[derive(Debug)]
+pub struct Value {
+    term: TermRef,
+    number: Option<u64>,
+}
+impl Value {
+    /// value decoded with the configured number encoding
+    pub fn as_number(&self) -> Option<u64> {
+        self.number
+    }
+}
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        enum Item<'a> {
+            Term(&'a Term, usize),
+            Str(&'static str),
+        }
+        // printed with explicit stack like `normalize`, normal forms may be deeply nested
+        let mut stack = vec![Item::Term(&self.term, 0)];
+        while let Some(item) = stack.pop() {
+            let (t, depth) = match item {
+                Item::Str(s) => {
+                    f.write_str(s)?;
+                    continue;
+                }
+                Item::Term(t, depth) => (t, depth),
+            };
+            match t {
+                Term::Var(i) => write!(f, "x{}", depth - i - 1)?,
+                Term::Global(_) | Term::Free(_) => f.write_str("?")?,
+                Term::Lam(b) => {
+                    write!(f, "\\x{depth}. ")?;
+                    stack.push(Item::Term(b, depth + 1));
+                }
+                Term::App(func, a) => {
+                    // pushed in reverse order of output
+                    if matches!(&**a, Term::Lam(_) | Term::App(_, _)) {
+                        stack.extend([Item::Str(")"), Item::Term(a, depth), Item::Str(" (")]);
+                    } else {
+                        stack.extend([Item::Term(a, depth), Item::Str(" ")]);
+                    }
+                    if matches!(&**func, Term::Lam(_)) {
+                        stack.extend([Item::Str(")"), Item::Term(func, depth), Item::Str("(")]);
+                    } else {
+                        stack.push(Item::Term(func, depth));
+                    }
+                }
+            }
+        }
+        if let Some(n) = self.number {
+            write!(f, " = {n}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Compiled lambda calculus definitions
+pub struct Program {
+    names: HashMap<String, usize>,
+    globals: Vec<TermRef>,
+    config: Config,
+}
+impl Program {
+    pub fn compile(src: &str, config: Config) -> Result<Self, Error> {
+        let defs = definitions(src)?;
+        let mut names = HashMap::with_capacity(defs.len());
+        for (idx, (_, name, _)) in defs.iter().enumerate() {
+            if names.insert(name.to_string(), idx).is_some() {
+                return Err(Error::Duplicate(name.to_string()));
+            }
+        }
+
+        let mut globals = Vec::with_capacity(defs.len());
+        for (idx, (line, name, body)) in defs.iter().enumerate() {
+            let global = |n: &str| match names.get(n) {
+                None => Err(Error::Undefined(n.to_string())),
+                Some(g) => {
+                    let allowed = match config.purity {
+                        Purity::PureLC => false,
+                        Purity::Let => *g < idx,
+                        Purity::LetRec => true,
+                    };
+                    if allowed {
+                        Ok(Rc::new(Term::Global(*g)))
+                    } else {
+                        Err(Error::Purity(n.to_string(), config.purity))
+                    }
+                }
+            };
+            let t = parse(body, *line, &global, config.num_encoding).map_err(|e| match e {
+                Error::Undefined(n) if n == *name && config.purity != Purity::LetRec => {
+                    Error::Purity(n, config.purity)
+                }
+                e => e,
+            })?;
+            globals.push(t);
+        }
+
+        Ok(Self {
+            names,
+            globals,
+            config,
+        })
+    }
+
+    /// Evaluate expression referring to definitions to normal form
+    pub fn eval(&self, expr: &str) -> Result<Value, Error> {
+        let global = |n: &str| match self.names.get(n) {
+            Some(g) => Ok(Rc::new(Term::Global(*g))),
+            None => Err(Error::Undefined(n.to_string())),
+        };
+        let t = parse(expr, 1, &global, self.config.num_encoding)?;
+        let mut machine = Machine {
+            globals: &self.globals,
+            steps: 0,
+            max_steps: self.config.max_steps,
+        };
+        let term = machine.normalize(t)?;
+        // values that are not numbers may diverge when applied to markers
+        machine.steps = 0;
+        let number = machine
+            .decode(&term, self.config.num_encoding)
+            .ok()
+            .flatten();
+        Ok(Value { term, number })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Config, Error, NumEncoding, Program, Purity};
+
+    fn config(purity: Purity, num_encoding: NumEncoding) -> Config {
+        Config {
+            purity,
+            num_encoding,
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn church_arithmetic() {
+        let prog = Program::compile(
+            "# church numerals\n\
+            succ = \\n f x. f (n f x)\n\
+            add = \\m n. m succ n\n\
+            mul = \\m n f. m (n f)",
+            config(Purity::Let, NumEncoding::Church),
+        )
+        .unwrap();
+        assert_eq!(prog.eval("add 2 3").unwrap().as_number(), Some(5));
+        assert_eq!(prog.eval("mul 3 4").unwrap().as_number(), Some(12));
+    }
+
+    #[test]
+    fn scott_recursion() {
+        let prog = Program::compile(
+            "zero = \\z _. z\n\
+            succ = \\n _ s. s n\n\
+            add = \\m n. m n\n  \
+              (\\p. succ (add p n))",
+            config(Purity::LetRec, NumEncoding::Scott),
+        )
+        .unwrap();
+        assert_eq!(prog.eval("add 4 3").unwrap().as_number(), Some(7));
+        assert_eq!(prog.eval("succ zero").unwrap().as_number(), Some(1));
+    }
+
+    #[test]
+    fn binary_scott_literal() {
+        let prog =
+            Program::compile("id = \\x. x", config(Purity::Let, NumEncoding::BinaryScott)).unwrap();
+        assert_eq!(prog.eval("id 37").unwrap().as_number(), Some(37));
+        assert_eq!(prog.eval("0").unwrap().as_number(), Some(0));
+    }
+
+    #[test]
+    fn purity() {
+        assert!(matches!(
+            Program::compile("f = \\x. f x", config(Purity::Let, NumEncoding::None)),
+            Err(Error::Purity(_, Purity::Let))
+        ));
+        assert!(matches!(
+            Program::compile(
+                "id = \\x. x\nk = \\x _. id x",
+                config(Purity::PureLC, NumEncoding::None)
+            ),
+            Err(Error::Purity(_, Purity::PureLC))
+        ));
+        assert!(matches!(
+            Program::compile("one = 1", config(Purity::Let, NumEncoding::None)),
+            Err(Error::Number(1))
+        ));
+    }
+
+    #[test]
+    fn deep_terms() {
+        // nesting far beyond what recursive traversal fits on a test thread stack
+        let prog =
+            Program::compile("id = \\x. x", config(Purity::Let, NumEncoding::Church)).unwrap();
+        assert_eq!(prog.eval("id 200000").unwrap().as_number(), Some(200000));
+        let printed = prog.eval("id 200000").unwrap().to_string();
+        assert!(printed.starts_with("\\x0. \\x1. x0 (x0 (x0"));
+        assert!(printed.ends_with(&format!("(x0 x1{} = 200000", ")".repeat(199_999))));
+    }
+
+    #[test]
+    fn normal_form() {
+        let prog =
+            Program::compile("k = \\x _. x", config(Purity::Let, NumEncoding::None)).unwrap();
+        assert_eq!(
+            prog.eval("k (\\a. a)").unwrap().to_string(),
+            "\\x0. \\x1. x1"
+        );
+    }
+}
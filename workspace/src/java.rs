@@ -3,6 +3,7 @@ extern crate alloc;
 use alloc::ffi::{CString, NulError};
 use std::{ffi::CStr, path::Path};
 
+use codewars_types::{catalog::Toolchain, KnownLangId};
 use rustix::{
     fd::{AsFd, BorrowedFd, OwnedFd},
     io::Errno,
@@ -10,31 +11,173 @@ use rustix::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    util::{call_command_at, fs},
-    Code, Config, WorkspaceObject,
+    util::{build_timeout, call_command_at, fs, toolchain, write_module},
+    Code, Config, SubstitutedToolchain, WorkspaceObject,
 };
 
 const STATE_PATH: &CStr = c"state.json";
 
-fn public_class(code: &str) -> Option<&str> {
-    let (_, t) = code.split_once("class")?;
-    return Some(
-        t.trim_start()
-            .split_once(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))?
-            .0,
-    );
+#[derive(Debug, PartialEq, Eq)]
+enum Token<'a> {
+    Ident(&'a str),
+    Symbol(char),
 }
 
+/// end of literal starting at `s` with `quote`, skipping escaped characters
+fn literal_len(s: &str, quote: &str) -> usize {
+    let mut idx = quote.len();
+    while idx < s.len() {
+        let t = &s[idx..];
+        if let Some(escaped) = t.strip_prefix('\\') {
+            idx += 1 + escaped.chars().next().map_or(0, char::len_utf8);
+        } else if t.starts_with(quote) {
+            return idx + quote.len();
+        } else {
+            idx += t.chars().next().unwrap().len_utf8();
+        }
+    }
+    s.len()
+}
+
+/// identifiers and symbols of java like source, without comments and literals
+fn tokens(src: &str) -> Vec<Token<'_>> {
+    let mut ret = Vec::new();
+    let mut s = src;
+    while let Some(c) = s.chars().next() {
+        let len = if c.is_whitespace() {
+            c.len_utf8()
+        } else if s.starts_with("//") {
+            s.find('\n').unwrap_or(s.len())
+        } else if let Some(comment) = s.strip_prefix("/*") {
+            comment.find("*/").map_or(s.len(), |i| i + 4)
+        } else if s.starts_with("\"\"\"") {
+            literal_len(s, "\"\"\"")
+        } else if c == '"' || c == '\'' {
+            literal_len(s, &s[..1])
+        } else if c.is_alphanumeric() || c == '_' || c == '$' {
+            let len = s
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(s.len());
+            ret.push(Token::Ident(&s[..len]));
+            len
+        } else {
+            ret.push(Token::Symbol(c));
+            c.len_utf8()
+        };
+        s = &s[len..];
+    }
+    ret
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct Header<'a> {
+    pub package: Option<String>,
+    /// public top level type, or first top level type if none is public
+    pub type_name: Option<&'a str>,
+}
+
+/// Scan package and top level type declarations of java or C# source
+///
+/// C# `namespace` blocks are transparent, their name is reported as package.
+pub(crate) fn header(src: &str) -> Header<'_> {
+    let tokens = tokens(src);
+    let mut header = Header::default();
+    let mut first_type = None;
+    // open blocks, `true` for namespace blocks
+    let mut blocks = Vec::new();
+    let mut depth = 0usize;
+    let mut namespace = false;
+    let mut public = false;
+    let mut idx = 0;
+    while idx < tokens.len() {
+        match tokens[idx] {
+            Token::Symbol('{') => {
+                blocks.push(namespace);
+                if !namespace {
+                    depth += 1;
+                }
+                namespace = false;
+                public = false;
+            }
+            Token::Symbol('}') => {
+                if blocks.pop() == Some(false) {
+                    depth -= 1;
+                }
+                public = false;
+            }
+            _ if depth != 0 => (),
+            Token::Symbol(';') => public = false,
+            Token::Ident(kw @ ("package" | "namespace")) => {
+                let mut package = String::new();
+                while let Some(t) = tokens.get(idx + 1) {
+                    match t {
+                        Token::Ident(i) => package.push_str(i),
+                        Token::Symbol('.') => package.push('.'),
+                        _ => break,
+                    }
+                    idx += 1;
+                }
+                header.package.get_or_insert(package);
+                namespace = kw == "namespace";
+            }
+            Token::Ident("public") => public = true,
+            Token::Ident("class" | "interface" | "enum" | "record" | "struct") => {
+                if let Some(Token::Ident(name)) = tokens.get(idx + 1) {
+                    if public {
+                        header.type_name = Some(*name);
+                        return header;
+                    }
+                    first_type.get_or_insert(*name);
+                    idx += 1;
+                }
+            }
+            _ => (),
+        }
+        idx += 1;
+    }
+    header.type_name = first_type;
+    header
+}
+
+pub(crate) fn public_class(code: &str) -> Option<&str> {
+    header(code).type_name
+}
+
+/// package qualified name of the top level type
+fn qualified_name(code: &str) -> Option<String> {
+    let header = header(code);
+    let name = header.type_name?;
+    Some(match header.package {
+        Some(p) => format!("{p}.{name}"),
+        None => name.to_string(),
+    })
+}
+
+/// qualified name and source of empty preloaded class in solution package
+fn preloaded(code: &str) -> (String, String) {
+    match header(code).package {
+        Some(p) => (format!("{p}.Preloaded"), format!("package {p};\n")),
+        None => ("Preloaded".to_string(), String::new()),
+    }
+}
+
+/// package qualified names of solution and test class
 #[derive(Serialize, Deserialize)]
-struct State<'a> {
-    main_class: &'a str,
-    test_class: &'a str,
+struct State {
+    main_class: String,
+    test_class: String,
 }
-impl<'a> State<'a> {
+impl State {
     fn path(&self) -> Result<(CString, CString), std::ffi::NulError> {
         Ok((
-            CString::new(format!("src/main/java/{}.java", self.main_class))?,
-            CString::new(format!("src/test/java/{}.java", self.test_class))?,
+            CString::new(format!(
+                "src/main/java/{}.java",
+                self.main_class.replace('.', "/")
+            ))?,
+            CString::new(format!(
+                "src/test/java/{}.java",
+                self.test_class.replace('.', "/")
+            ))?,
         ))
     }
 }
@@ -56,6 +199,8 @@ pub struct OpenError(#[from] OpenErrorInner);
 enum CreateErrorInner {
     #[error("io error")]
     Io(#[source] Errno),
+    #[error("failed to write class")]
+    WriteClass(#[source] std::io::Error),
     #[error("class name contains nul")]
     InvalidClassName(#[source] NulError),
     #[error("failed to get public class name")]
@@ -86,36 +231,41 @@ impl Java {
             test_path,
         })
     }
-    pub fn create(root: &Path, project: Config) -> Result<Self, CreateError> {
+    pub fn create(
+        root: &Path,
+        project: Config,
+    ) -> Result<(Self, Option<SubstitutedToolchain>), CreateError> {
+        let (toolchain, substituted) = toolchain(KnownLangId::Java, project.version_id);
         let root = fs::open_dirfd(root).map_err(CreateErrorInner::Io)?;
 
         let state = State {
-            main_class: public_class(&project.code).ok_or(CreateErrorInner::UnknownClassName)?,
-            test_class: public_class(&project.fixture).ok_or(CreateErrorInner::UnknownClassName)?,
+            main_class: qualified_name(project.code).ok_or(CreateErrorInner::UnknownClassName)?,
+            test_class: qualified_name(project.fixture)
+                .ok_or(CreateErrorInner::UnknownClassName)?,
         };
         let (code_path, test_path) = state.path().map_err(CreateErrorInner::InvalidClassName)?;
 
-        fn inner(
-            root: BorrowedFd,
-            project: &Config,
-            state: &State,
-            code_path: &CStr,
-            test_path: &CStr,
-        ) -> Result<(), Errno> {
-            fs::mkdirat(root, c"src")?;
-
-            fs::mkdirat(root, c"src/main")?;
-            fs::mkdirat(root, c"src/main/java")?;
-            fs::write(root, code_path, project.code)?;
-
-            fs::mkdirat(root, c"src/test")?;
-            fs::mkdirat(root, c"src/test/java")?;
-            fs::write(root, test_path, project.fixture)?;
+        let write_class = |base, class: &str, code| {
+            write_module(root.as_fd(), base, class, "java", code)
+                .map_err(CreateErrorInner::WriteClass)
+        };
+        write_class("src/main/java", &state.main_class, project.code)?;
+        write_class("src/test/java", &state.test_class, project.fixture)?;
+        if project.has_preload {
+            let (class, src) = preloaded(project.code);
+            write_class("src/main/java", &class, &src)?;
+        }
 
+        fn inner(root: BorrowedFd, state: &State, toolchain: &Toolchain) -> Result<(), Errno> {
             fs::write(
                 root,
                 c"build.gradle.kts",
-                include_str!("./java/build.gradle.kts"),
+                format!(
+                    include_str!("./java/build.gradle.kts"),
+                    java_version = toolchain.version,
+                    test_framework = toolchain.test_framework,
+                    junit_version = toolchain.test_framework.rsplit(':').next().unwrap()
+                ),
             )?;
 
             fs::write(root, STATE_PATH, serde_json::to_vec(state).unwrap())?;
@@ -123,14 +273,16 @@ impl Java {
             Ok(())
         }
 
-        inner(root.as_fd(), &project, &state, &code_path, &test_path)
-            .map_err(CreateErrorInner::Io)?;
+        inner(root.as_fd(), &state, toolchain).map_err(CreateErrorInner::Io)?;
 
-        Ok(Self {
-            root,
-            code_path,
-            test_path,
-        })
+        Ok((
+            Self {
+                root,
+                code_path,
+                test_path,
+            },
+            substituted,
+        ))
     }
 }
 impl WorkspaceObject for Java {
@@ -141,7 +293,7 @@ impl WorkspaceObject for Java {
         })
     }
     fn clean_build(&self) -> Result<(), std::io::Error> {
-        call_command_at(self.root.as_fd(), "gradle", ["clean"])?;
+        call_command_at(self.root.as_fd(), "gradle", ["clean"], build_timeout())?.check()?;
         Ok(())
     }
     fn clean_session(&self) -> Result<(), std::io::Error> {
@@ -153,7 +305,7 @@ impl WorkspaceObject for Java {
 
 #[cfg(test)]
 mod test {
-    use super::public_class;
+    use super::{header, preloaded, public_class, qualified_name, Header};
 
     #[test]
     fn simple_class() {
@@ -162,4 +314,80 @@ mod test {
             Some("Cls")
         )
     }
+
+    #[test]
+    fn comments_and_literals() {
+        assert_eq!(
+            public_class(
+                "// this class solves the kata\n\
+                /* class Block */\n\
+                class Helper { String s = \"class Str {\"; char c = '{'; }\n\
+                public final class Solution {}"
+            ),
+            Some("Solution")
+        );
+        assert_eq!(
+            public_class("class Outer { public class Inner {} }"),
+            Some("Outer")
+        );
+    }
+
+    #[test]
+    fn kinds() {
+        assert_eq!(public_class("public interface Shape {}"), Some("Shape"));
+        assert_eq!(public_class("public enum Dir { N, S }"), Some("Dir"));
+        assert_eq!(
+            public_class("public record Point(int x, int y) {}"),
+            Some("Point")
+        );
+        assert_eq!(public_class("public @interface Ann {}"), Some("Ann"));
+    }
+
+    #[test]
+    fn package() {
+        let src =
+            "/* header */ package com.codewars.kata;\nimport java.util.*;\npublic class Kata {}";
+        assert_eq!(
+            header(src),
+            Header {
+                package: Some("com.codewars.kata".to_string()),
+                type_name: Some("Kata"),
+            }
+        );
+        assert_eq!(
+            qualified_name(src).as_deref(),
+            Some("com.codewars.kata.Kata")
+        );
+        assert_eq!(
+            preloaded(src),
+            (
+                "com.codewars.kata.Preloaded".to_string(),
+                "package com.codewars.kata;\n".to_string()
+            )
+        );
+        assert_eq!(
+            preloaded("public class Kata {}"),
+            ("Preloaded".to_string(), String::new())
+        );
+    }
+
+    #[test]
+    fn csharp_namespace() {
+        let src = "using NUnit.Framework;\n\
+            namespace Solution.Tests {\n\
+                [TestFixture]\n\
+                public class KataTest { void F() { var c = new Kata(); } }\n\
+            }";
+        assert_eq!(
+            header(src),
+            Header {
+                package: Some("Solution.Tests".to_string()),
+                type_name: Some("KataTest"),
+            }
+        );
+        assert_eq!(
+            public_class("namespace Solution;\npublic static class Kata {}"),
+            Some("Kata")
+        );
+    }
 }
@@ -1,11 +1,24 @@
-plugins {
+plugins {{
     `java-library`
-}
+}}
 
-repositories {
+java {{
+    toolchain {{
+        languageVersion.set(JavaLanguageVersion.of({java_version}))
+    }}
+}}
+
+repositories {{
     mavenCentral()
-}
+}}
+
+dependencies {{
+    testImplementation("{test_framework}")
+    // fixtures written for JUnit 4 run on the vintage engine
+    testImplementation("junit:junit:4.13.2")
+    testRuntimeOnly("org.junit.vintage:junit-vintage-engine:{junit_version}")
+}}
 
-dependencies {
-    testImplementation("junit:junit:4.13")
-}
+tasks.test {{
+    useJUnitPlatform()
+}}
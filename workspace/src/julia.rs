@@ -0,0 +1,143 @@
+use std::{ffi::CStr, path::Path};
+
+use codewars_types::KnownLangId;
+use rustix::{
+    fd::{AsFd, OwnedFd},
+    io::Errno,
+};
+use uuid::Uuid;
+
+use crate::{
+    util::{fs, toolchain},
+    Code, Config, SubstitutedToolchain, WorkspaceObject,
+};
+
+const PRELOADED_PATH: &CStr = c"preloaded.jl";
+const CODE_PATH: &CStr = c"solution.jl";
+const TEST_PATH: &CStr = c"test/sample.jl";
+
+/// package module name in CamelCase derived from kata slug
+fn module_name(slug: &str) -> String {
+    let mut ret = String::with_capacity(slug.len());
+    if !slug.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        ret.push_str("Kata");
+    }
+    for word in slug.split(|c: char| !c.is_ascii_alphanumeric()) {
+        let mut chars = word.chars();
+        if let Some(c) = chars.next() {
+            ret.push(c.to_ascii_uppercase());
+            ret.extend(chars);
+        }
+    }
+    ret
+}
+
+/// package uuid derived from kata url, so that recreated workspaces keep their uuid
+fn package_uuid(slug: &str) -> Uuid {
+    Uuid::new_v5(
+        &Uuid::NAMESPACE_URL,
+        format!("https://www.codewars.com/kata/{slug}").as_bytes(),
+    )
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct OpenError(Errno);
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct CreateError(#[from] Errno);
+
+pub struct Julia {
+    root: OwnedFd,
+}
+impl Julia {
+    pub fn open(root: &Path) -> Result<Self, OpenError> {
+        Ok(Self {
+            root: fs::open_dirfd(root).map_err(OpenError)?,
+        })
+    }
+    pub fn create(
+        root: &Path,
+        project: Config,
+    ) -> Result<(Self, Option<SubstitutedToolchain>), CreateError> {
+        let (toolchain, substituted) = toolchain(KnownLangId::Julia, project.version_id);
+        let root = fs::open_dirfd(root)?;
+
+        let module = module_name(project.slug);
+        fs::write(
+            root.as_fd(),
+            c"Project.toml",
+            format!(
+                include_str!("./julia/Project.toml"),
+                module = module,
+                uuid = package_uuid(project.slug),
+                julia_version = toolchain.version
+            ),
+        )?;
+        fs::write(root.as_fd(), CODE_PATH, project.code)?;
+        if project.has_preload {
+            fs::write(root.as_fd(), PRELOADED_PATH, "")?;
+        }
+
+        fs::mkdirat(root.as_fd(), c"src")?;
+        fs::write(
+            root.as_fd(),
+            format!("src/{module}.jl"),
+            format!(include_str!("./julia/Package.jl"), module = module),
+        )?;
+
+        fs::mkdirat(root.as_fd(), c"test")?;
+        fs::write(root.as_fd(), TEST_PATH, project.fixture)?;
+        fs::write(
+            root.as_fd(),
+            c"test/Project.toml",
+            include_str!("./julia/test/Project.toml"),
+        )?;
+        fs::write(
+            root.as_fd(),
+            c"test/runtests.jl",
+            format!(
+                include_str!("./julia/runtests.jl"),
+                preloaded = if project.has_preload {
+                    "include(joinpath(@__DIR__, \"..\", \"preloaded.jl\"))\n"
+                } else {
+                    ""
+                }
+            ),
+        )?;
+        Ok((Self { root }, substituted))
+    }
+}
+impl WorkspaceObject for Julia {
+    fn get_code(&self) -> Result<Code, std::io::Error> {
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), CODE_PATH)?,
+            fixture: fs::read_to_string(self.root.as_fd(), TEST_PATH)?,
+        })
+    }
+    fn clean_build(&self) -> Result<(), std::io::Error> {
+        Ok(())
+    }
+    fn clean_session(&self) -> Result<(), std::io::Error> {
+        fs::remove_at(self.root.as_fd(), c"Manifest.toml")?;
+        fs::remove_at(self.root.as_fd(), c"test/Manifest.toml")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{module_name, package_uuid};
+
+    #[test]
+    fn package() {
+        assert_eq!(module_name("multiply-two-numbers"), "MultiplyTwoNumbers");
+        assert_eq!(module_name("2-sum"), "Kata2Sum");
+
+        assert_eq!(
+            package_uuid("multiply").to_string(),
+            "53ea0c58-4613-5ab3-85b1-a8530b3ab53f"
+        );
+    }
+}
@@ -0,0 +1,4 @@
+# solution is included into `Main` by test/runtests.jl like on Codewars, this module
+# only makes the workspace a package for `Pkg.test`
+module {module}
+end
@@ -0,0 +1,2 @@
+{preloaded}include(joinpath(@__DIR__, "..", "solution.jl"))
+include(joinpath(@__DIR__, "sample.jl"))
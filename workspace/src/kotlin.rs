@@ -1,17 +1,19 @@
 use std::{ffi::CStr, path::Path};
 
+use codewars_types::KnownLangId;
 use rustix::{
     fd::{AsFd, OwnedFd},
     io::Errno,
 };
 
 use crate::{
-    util::{call_command_at, fs},
-    Code, Config, WorkspaceObject,
+    util::{build_timeout, call_command_at, fs, toolchain},
+    Code, Config, SubstitutedToolchain, WorkspaceObject,
 };
 
 const CODE_PATH: &CStr = c"src/main/kotlin/library.kt";
 const TEST_PATH: &CStr = c"src/test/kotlin/sample.kt";
+const PRELOADED_PATH: &CStr = c"src/main/kotlin/preloaded.kt";
 
 #[derive(Debug, thiserror::Error)]
 #[error(transparent)]
@@ -30,7 +32,11 @@ impl Kotlin {
             root: fs::open_dirfd(root).map_err(OpenError)?,
         })
     }
-    pub fn create(root: &Path, project: Config, patch: u16) -> Result<Self, CreateError> {
+    pub fn create(
+        root: &Path,
+        project: Config,
+    ) -> Result<(Self, Option<SubstitutedToolchain>), CreateError> {
+        let (toolchain, substituted) = toolchain(KnownLangId::Kotlin, project.version_id);
         let root = fs::open_dirfd(root)?;
 
         fs::write(
@@ -38,7 +44,7 @@ impl Kotlin {
             c"build.gradle.kts",
             format!(
                 include_str!("./kotlin/build.gradle.kts"),
-                kotlin_version = format_args!("{}.{patch}", project.version_id)
+                kotlin_version = toolchain.version
             ),
         )?;
 
@@ -47,12 +53,15 @@ impl Kotlin {
         fs::mkdirat(root.as_fd(), c"src/main")?;
         fs::mkdirat(root.as_fd(), c"src/main/kotlin")?;
         fs::write(root.as_fd(), CODE_PATH, project.code)?;
+        if project.has_preload {
+            fs::write(root.as_fd(), PRELOADED_PATH, "")?;
+        }
 
         fs::mkdirat(root.as_fd(), c"src/test")?;
         fs::mkdirat(root.as_fd(), c"src/test/kotlin")?;
         fs::write(root.as_fd(), TEST_PATH, project.fixture)?;
 
-        Ok(Self { root })
+        Ok((Self { root }, substituted))
     }
 }
 impl WorkspaceObject for Kotlin {
@@ -63,7 +72,7 @@ impl WorkspaceObject for Kotlin {
         })
     }
     fn clean_build(&self) -> Result<(), std::io::Error> {
-        call_command_at(self.root.as_fd(), "gradle", ["clean"])?;
+        call_command_at(self.root.as_fd(), "gradle", ["clean"], build_timeout())?.check()?;
         Ok(())
     }
     fn clean_session(&self) -> Result<(), std::io::Error> {
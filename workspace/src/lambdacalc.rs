@@ -0,0 +1,210 @@
+use std::{ffi::CStr, io, path::Path};
+
+use rustix::fd::{AsFd, OwnedFd};
+
+use crate::{
+    interp::{
+        cases,
+        lc::{self, NumEncoding, Purity},
+    },
+    util::fs,
+    Code, Config, WorkspaceObject,
+};
+
+const PRELOADED_FILE: &CStr = c"preloaded.lc";
+const CODE_FILE: &CStr = c"solution.lc";
+const FIXTURE_FILE: &CStr = c"test.js";
+const CONFIG_FILE: &CStr = c"interp.json";
+
+/// Find string value of `key` in `LC.configure({ key: "value" })` call of fixture
+fn fixture_option<'a>(fixture: &'a str, key: &str) -> Option<&'a str> {
+    fixture.match_indices(key).find_map(|(idx, _)| {
+        let s = fixture[idx + key.len()..]
+            .trim_start_matches(['"', '\''])
+            .trim_start()
+            .strip_prefix(':')?
+            .trim_start()
+            .strip_prefix(['"', '\''])?;
+        let end = s.find(|c: char| !c.is_ascii_alphanumeric())?;
+        Some(&s[..end])
+    })
+}
+
+fn detect_config(fixture: &str) -> lc::Config {
+    let purity = match fixture_option(fixture, "purity") {
+        Some("Let") => Purity::Let,
+        Some("PureLC") => Purity::PureLC,
+        _ => Purity::LetRec,
+    };
+    let num_encoding = match fixture_option(fixture, "numEncoding") {
+        Some("Church") => NumEncoding::Church,
+        Some("Scott") => NumEncoding::Scott,
+        Some("BinaryScott") => NumEncoding::BinaryScott,
+        _ => NumEncoding::None,
+    };
+    lc::Config {
+        purity,
+        num_encoding,
+        ..lc::Config::default()
+    }
+}
+
+/// Lambda calculus term of JavaScript call like `solution.add(1)(f(2, x))`
+fn expression(src: &str) -> Option<String> {
+    if let Some(n) = cases::number(src) {
+        return Some(n.to_string());
+    }
+    if let Some((name, calls)) = cases::call(src) {
+        let mut ret = format!("({name}");
+        for arg in calls.iter().flatten() {
+            ret.push(' ');
+            ret.push_str(&expression(arg)?);
+        }
+        ret.push(')');
+        return Some(ret);
+    }
+    let name = src.rsplit('.').next()?;
+    (!name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')).then(|| name.into())
+}
+
+/// Expression and expected number of `assert.<method>(term, number)` in fixture
+fn test_cases(fixture: &str) -> cases::Cases<(String, String, u64)> {
+    cases::extract(fixture, |a| {
+        let actual = a.args.first()?;
+        Some((
+            actual.to_string(),
+            expression(actual)?,
+            cases::number(a.args.get(1)?)?,
+        ))
+    })
+}
+
+pub struct LambdaCalc {
+    root: OwnedFd,
+}
+impl LambdaCalc {
+    pub fn create(root: &Path, project: Config) -> io::Result<Self> {
+        let root = fs::open_dirfd(root)?;
+
+        fs::write(root.as_fd(), CODE_FILE, project.code)?;
+        fs::write(root.as_fd(), FIXTURE_FILE, project.fixture)?;
+        if project.has_preload {
+            fs::write(root.as_fd(), PRELOADED_FILE, "")?;
+        }
+        fs::write(
+            root.as_fd(),
+            CONFIG_FILE,
+            serde_json::to_string_pretty(&detect_config(project.fixture)).unwrap(),
+        )?;
+
+        Ok(Self { root })
+    }
+    pub fn open(root: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self {
+            root: fs::open_dirfd(root.as_ref())?,
+        })
+    }
+}
+impl LambdaCalc {
+    /// compile preloaded and solution definitions with workspace config
+    fn compile(&self) -> Result<lc::Program, io::Error> {
+        let config: lc::Config =
+            serde_json::from_str(&fs::read_to_string(self.root.as_fd(), CONFIG_FILE)?)?;
+        let mut code = match fs::read_to_string(self.root.as_fd(), PRELOADED_FILE) {
+            Ok(p) => p + "\n",
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+        // preloaded definitions come first so `Let` purity can refer to them
+        code.push_str(&fs::read_to_string(self.root.as_fd(), CODE_FILE)?);
+        lc::Program::compile(&code, config).map_err(io::Error::other)
+    }
+}
+impl WorkspaceObject for LambdaCalc {
+    fn get_code(&self) -> Result<Code, io::Error> {
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), CODE_FILE)?,
+            fixture: fs::read_to_string(self.root.as_fd(), FIXTURE_FILE)?,
+        })
+    }
+    fn clean_build(&self) -> Result<(), io::Error> {
+        Ok(())
+    }
+    fn clean_session(&self) -> Result<(), io::Error> {
+        Ok(())
+    }
+    fn eval(&self, input: &str) -> Result<Option<String>, io::Error> {
+        let value = self.compile()?.eval(input).map_err(io::Error::other)?;
+        Ok(Some(value.to_string()))
+    }
+    fn local_test(&self) -> Result<bool, io::Error> {
+        let program = self.compile()?;
+        let fixture = fs::read_to_string(self.root.as_fd(), FIXTURE_FILE)?;
+        let cases = test_cases(&fixture);
+        let outcomes: Vec<_> = cases
+            .cases
+            .into_iter()
+            .map(|(case, expr, expected)| {
+                let failure = match program.eval(&expr) {
+                    Ok(v) if v.as_number() == Some(expected) => None,
+                    Ok(v) => Some(format!("expected {expected}, got {v}")),
+                    Err(e) => Some(e.to_string()),
+                };
+                (case, failure)
+            })
+            .collect();
+        cases::report(&outcomes, &cases.skipped)?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{detect_config, test_cases};
+    use crate::interp::lc::{NumEncoding, Purity};
+
+    #[test]
+    fn configure() {
+        let cfg = detect_config(
+            r#"import { assert, LC, getSolution } from "./lc-test.js";
+LC.configure({ purity: "Let", numEncoding: "BinaryScott" });
+const solution = LC.compile(getSolution());"#,
+        );
+        assert_eq!(cfg.purity, Purity::Let);
+        assert_eq!(cfg.num_encoding, NumEncoding::BinaryScott);
+
+        let cfg = detect_config(r#"LC.configure({"purity":"PureLC"})"#);
+        assert_eq!(cfg.purity, Purity::PureLC);
+        assert_eq!(cfg.num_encoding, NumEncoding::None);
+    }
+
+    #[test]
+    fn fixture_cases() {
+        let fixture = r#"const { add, zero } = LC.compile(getSolution());
+describe("add", () => {
+  it("small", () => {
+    assert.numEql(add(1, 2), 3);
+    assert.numEql(solution.add(zero)(add(2, 2)), 4);
+    assert.numEql(zero, 0);
+    assert.equal(add(1, 2).toString(), "3");
+  });
+});"#;
+        let cases = test_cases(fixture);
+        assert_eq!(
+            cases.cases,
+            [
+                ("add(1, 2)".to_string(), "(add 1 2)".to_string(), 3),
+                (
+                    "solution.add(zero)(add(2, 2))".to_string(),
+                    "(add zero (add 2 2))".to_string(),
+                    4
+                ),
+                ("zero".to_string(), "zero".to_string(), 0),
+            ]
+        );
+        assert_eq!(
+            cases.skipped,
+            [r#"assert.equal(add(1, 2).toString(), "3")"#]
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use std::{ffi::CStr, io, path::Path};
+
+use codewars_types::KnownLangId;
+use rustix::fd::{AsFd, OwnedFd};
+
+use crate::{
+    util::{build_timeout, call_command_at, fs, toolchain},
+    Code, Config, SubstitutedToolchain, WorkspaceObject,
+};
+
+pub struct Lean {
+    root: OwnedFd,
+}
+
+const PRELOADED_FILE: &CStr = c"Preloaded.lean";
+const CODE_FILE: &CStr = c"Solution.lean";
+const FIXTURE_FILE: &CStr = c"SolutionTest.lean";
+
+impl Lean {
+    pub fn create(
+        root: &Path,
+        project: Config,
+    ) -> io::Result<(Self, Option<SubstitutedToolchain>)> {
+        let (toolchain, substituted) = toolchain(KnownLangId::Lean, project.version_id);
+        let root = fs::open_dirfd(root)?;
+
+        fs::write(root.as_fd(), CODE_FILE, project.code)?;
+        fs::write(root.as_fd(), FIXTURE_FILE, project.fixture)?;
+        fs::write(
+            root.as_fd(),
+            c"lean-toolchain",
+            // lean toolchain release tags are prefixed with `v`
+            format!("leanprover/lean4:v{}\n", toolchain.version),
+        )?;
+        fs::write(
+            root.as_fd(),
+            c"lakefile.lean",
+            format!(
+                include_str!("./lean/lakefile.lean"),
+                package = project.slug,
+                preloaded = if project.has_preload {
+                    "lean_lib Preloaded\n"
+                } else {
+                    ""
+                }
+            ),
+        )?;
+
+        if project.has_preload {
+            fs::write(root.as_fd(), PRELOADED_FILE, "")?;
+        }
+
+        Ok((Self { root }, substituted))
+    }
+    pub fn open(root: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self {
+            root: fs::open_dirfd(root.as_ref())?,
+        })
+    }
+}
+impl WorkspaceObject for Lean {
+    fn get_code(&self) -> Result<Code, io::Error> {
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), CODE_FILE)?,
+            fixture: fs::read_to_string(self.root.as_fd(), FIXTURE_FILE)?,
+        })
+    }
+    fn clean_build(&self) -> Result<(), io::Error> {
+        call_command_at(self.root.as_fd(), "lake", ["clean"], build_timeout())?.check()?;
+        Ok(())
+    }
+    fn clean_session(&self) -> Result<(), io::Error> {
+        fs::remove_dir_all_at(self.root.as_fd(), c".lake")?;
+        fs::remove_at(self.root.as_fd(), c"lake-manifest.json")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,9 @@
+import Lake
+open Lake DSL
+
+package «{package}»
+
+{preloaded}lean_lib Solution
+
+@[default_target]
+lean_lib SolutionTest
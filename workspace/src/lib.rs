@@ -1,6 +1,21 @@
 use std::{error, io};
 
+use codewars_types::catalog::Toolchain;
+
 mod util;
+pub use util::process;
+
+pub mod interp;
+
+pub mod probe;
+
+pub mod sandbox;
+
+pub mod template;
+pub use template::Custom;
+
+mod asm;
+mod dotnet;
 
 pub struct Config<'a> {
     pub version_id: &'a str,
@@ -9,6 +24,18 @@ pub struct Config<'a> {
     pub fixture: &'a str,
     pub has_preload: bool,
 }
+/// Newest catalog toolchain of the language used for a version missing from the catalog
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "unknown {} version {version_id}, using toolchain of version {}",
+    toolchain.lang,
+    toolchain.version_id
+)]
+pub struct SubstitutedToolchain {
+    pub version_id: String,
+    pub toolchain: &'static Toolchain,
+}
+
 pub struct Code {
     pub solution: String,
     pub fixture: String,
@@ -21,6 +48,14 @@ where
     fn get_code(&self) -> Result<Code, E>;
     fn clean_build(&self) -> Result<(), E>;
     fn clean_session(&self) -> Result<(), E>;
+    /// Evaluate `input` with a local interpreter, `None` if the language has none
+    fn eval(&self, _input: &str) -> Result<Option<String>, E> {
+        Ok(None)
+    }
+    /// Run sample tests locally, `false` if the workspace has no test command
+    fn local_test(&self) -> Result<bool, E> {
+        Ok(false)
+    }
 }
 
 pub mod rust;
@@ -43,3 +78,90 @@ pub use typescript::TypeScript;
 
 pub mod scala;
 pub use scala::Scala;
+
+pub mod lean;
+pub use lean::Lean;
+
+pub mod agda;
+pub use agda::Agda;
+
+pub mod idris;
+pub use idris::Idris;
+
+pub mod csharp;
+pub use csharp::CSharp;
+
+pub mod fsharp;
+pub use fsharp::FSharp;
+
+pub mod elixir;
+pub use elixir::Elixir;
+
+pub mod erlang;
+pub use erlang::Erlang;
+
+pub mod ruby;
+pub use ruby::Ruby;
+
+pub mod nasm;
+pub use nasm::Nasm;
+
+pub mod riscv;
+pub use riscv::RiscV;
+
+pub mod clojure;
+pub use clojure::Clojure;
+
+pub mod racket;
+pub use racket::Racket;
+
+pub mod purescript;
+pub use purescript::PureScript;
+
+pub mod elm;
+pub use elm::Elm;
+
+pub mod sql;
+pub use sql::Sql;
+
+pub mod shell;
+pub use shell::Shell;
+
+pub mod swift;
+pub use swift::Swift;
+
+pub mod dart;
+pub use dart::Dart;
+
+pub mod julia;
+pub use julia::Julia;
+
+pub mod r;
+pub use r::R;
+
+pub mod lua;
+pub use lua::Lua;
+
+pub mod nim;
+pub use nim::Nim;
+
+pub mod crystal;
+pub use crystal::Crystal;
+
+pub mod d;
+pub use d::D;
+
+pub mod prolog;
+pub use prolog::Prolog;
+
+pub mod commonlisp;
+pub use commonlisp::CommonLisp;
+
+pub mod fortran;
+pub use fortran::Fortran;
+
+pub mod brainfuck;
+pub use brainfuck::BrainFuck;
+
+pub mod lambdacalc;
+pub use lambdacalc::LambdaCalc;
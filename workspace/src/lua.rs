@@ -0,0 +1,78 @@
+use std::{ffi::CStr, path::Path};
+
+use codewars_types::KnownLangId;
+use rustix::{
+    fd::{AsFd, OwnedFd},
+    io::Errno,
+};
+
+use crate::{
+    util::{fs, toolchain},
+    Code, Config, SubstitutedToolchain, WorkspaceObject,
+};
+
+const PRELOADED_PATH: &CStr = c"preloaded.lua";
+const CODE_PATH: &CStr = c"solution.lua";
+const TEST_PATH: &CStr = c"spec/sample_spec.lua";
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct OpenError(Errno);
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct CreateError(#[from] Errno);
+
+pub struct Lua {
+    root: OwnedFd,
+}
+impl Lua {
+    pub fn open(root: &Path) -> Result<Self, OpenError> {
+        Ok(Self {
+            root: fs::open_dirfd(root).map_err(OpenError)?,
+        })
+    }
+    pub fn create(
+        root: &Path,
+        project: Config,
+    ) -> Result<(Self, Option<SubstitutedToolchain>), CreateError> {
+        let (toolchain, substituted) = toolchain(KnownLangId::Lua, project.version_id);
+        let root = fs::open_dirfd(root)?;
+
+        fs::write(
+            root.as_fd(),
+            c".busted",
+            format!(
+                include_str!("./lua/busted"),
+                interpreter = toolchain.option("interpreter").unwrap_or("lua")
+            ),
+        )?;
+        fs::write(root.as_fd(), CODE_PATH, project.code)?;
+        if project.has_preload {
+            // required by solution as module `preloaded`
+            fs::write(root.as_fd(), PRELOADED_PATH, "")?;
+        }
+
+        fs::mkdirat(root.as_fd(), c"spec")?;
+        fs::write(root.as_fd(), TEST_PATH, project.fixture)?;
+
+        Ok((Self { root }, substituted))
+    }
+}
+impl WorkspaceObject for Lua {
+    fn get_code(&self) -> Result<Code, std::io::Error> {
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), CODE_PATH)?,
+            fixture: fs::read_to_string(self.root.as_fd(), TEST_PATH)?,
+        })
+    }
+    fn clean_build(&self) -> Result<(), std::io::Error> {
+        Ok(())
+    }
+    fn clean_session(&self) -> Result<(), std::io::Error> {
+        fs::remove_dir_all_at(self.root.as_fd(), c"lua_modules")?;
+        fs::remove_dir_all_at(self.root.as_fd(), c".luarocks")?;
+        fs::remove_at(self.root.as_fd(), c"luacov.stats.out")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,32 @@
+use std::{io, path::Path};
+
+use crate::{
+    asm::{Project, Toolchain},
+    Code, Config, WorkspaceObject,
+};
+
+const TOOLCHAIN: Toolchain = Toolchain {
+    code_file: c"solution.asm",
+    makefile: include_str!("./nasm/Makefile"),
+};
+
+pub struct Nasm(Project);
+impl Nasm {
+    pub fn create(root: &Path, project: Config) -> io::Result<Self> {
+        Project::create(root, project, &TOOLCHAIN).map(Self)
+    }
+    pub fn open(root: impl AsRef<Path>) -> io::Result<Self> {
+        Project::open(root.as_ref(), &TOOLCHAIN).map(Self)
+    }
+}
+impl WorkspaceObject for Nasm {
+    fn get_code(&self) -> Result<Code, io::Error> {
+        self.0.get_code()
+    }
+    fn clean_build(&self) -> Result<(), io::Error> {
+        self.0.clean_build()
+    }
+    fn clean_session(&self) -> Result<(), io::Error> {
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+use std::{ffi::CStr, path::Path};
+
+use codewars_types::KnownLangId;
+use rustix::{
+    fd::{AsFd, OwnedFd},
+    io::Errno,
+};
+
+use crate::{
+    util::{fs, package_name, toolchain},
+    Code, Config, SubstitutedToolchain, WorkspaceObject,
+};
+
+const PRELOADED_PATH: &CStr = c"src/preloaded.nim";
+const CODE_PATH: &CStr = c"src/solution.nim";
+// nimble only runs tests in files starting with `t`
+const TEST_PATH: &CStr = c"tests/tsample.nim";
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct OpenError(Errno);
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct CreateError(#[from] Errno);
+
+pub struct Nim {
+    root: OwnedFd,
+}
+impl Nim {
+    pub fn open(root: &Path) -> Result<Self, OpenError> {
+        Ok(Self {
+            root: fs::open_dirfd(root).map_err(OpenError)?,
+        })
+    }
+    pub fn create(
+        root: &Path,
+        project: Config,
+    ) -> Result<(Self, Option<SubstitutedToolchain>), CreateError> {
+        let (toolchain, substituted) = toolchain(KnownLangId::Nim, project.version_id);
+        let root = fs::open_dirfd(root)?;
+
+        fs::mkdirat(root.as_fd(), c"src")?;
+        fs::write(root.as_fd(), CODE_PATH, project.code)?;
+        if project.has_preload {
+            fs::write(root.as_fd(), PRELOADED_PATH, "")?;
+        }
+
+        fs::mkdirat(root.as_fd(), c"tests")?;
+        fs::write(root.as_fd(), TEST_PATH, project.fixture)?;
+        fs::write(
+            root.as_fd(),
+            c"tests/config.nims",
+            include_str!("./nim/config.nims"),
+        )?;
+
+        fs::write(
+            root.as_fd(),
+            format!("{}.nimble", package_name(project.slug)),
+            format!(
+                include_str!("./nim/challenge.nimble"),
+                nim_version = toolchain.version
+            ),
+        )?;
+
+        Ok((Self { root }, substituted))
+    }
+}
+impl WorkspaceObject for Nim {
+    fn get_code(&self) -> Result<Code, std::io::Error> {
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), CODE_PATH)?,
+            fixture: fs::read_to_string(self.root.as_fd(), TEST_PATH)?,
+        })
+    }
+    fn clean_build(&self) -> Result<(), std::io::Error> {
+        fs::remove_at(self.root.as_fd(), c"tests/tsample")?;
+        Ok(())
+    }
+    fn clean_session(&self) -> Result<(), std::io::Error> {
+        fs::remove_dir_all_at(self.root.as_fd(), c"nimbledeps")?;
+        fs::remove_at(self.root.as_fd(), c"nimble.develop")?;
+        fs::remove_at(self.root.as_fd(), c"nimble.paths")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,317 @@
+//! Check that tools used by a workspace are installed before creating it.
+
+use std::{
+    env,
+    fmt::{self, Display},
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use codewars_types::{catalog, KnownLangId};
+
+/// How the version reported by a tool is compared with the expected version
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionCheck {
+    /// only check that tool exists
+    None,
+    /// reported version starts with expected version
+    Prefix,
+    /// reported version is not older than expected version
+    AtLeast,
+}
+
+#[derive(Debug)]
+pub struct Tool {
+    pub program: &'static str,
+    pub version_args: &'static [&'static str],
+    pub check: VersionCheck,
+    /// install command, `{version}` is replaced with expected version
+    pub install: &'static str,
+}
+
+macro_rules! tool {
+    ($program:literal, $install:literal) => {
+        Tool {
+            program: $program,
+            version_args: &["--version"],
+            check: VersionCheck::None,
+            install: $install,
+        }
+    };
+    ($program:literal, [$($arg:literal),*], $check:ident, $install:literal) => {
+        Tool {
+            program: $program,
+            version_args: &[$($arg),*],
+            check: VersionCheck::$check,
+            install: $install,
+        }
+    };
+}
+
+/// Tools required to build and test workspace of `lang`
+pub fn tools(lang: KnownLangId) -> &'static [Tool] {
+    match lang {
+        KnownLangId::Rust => &[
+            tool!("cargo", "rustup toolchain install {version}"),
+            tool!(
+                "rustc",
+                ["--version"],
+                AtLeast,
+                "rustup toolchain install {version}"
+            ),
+        ],
+        KnownLangId::Haskell => &[
+            tool!("ghc", ["--version"], Prefix, "ghcup install ghc {version}"),
+            tool!("cabal", "ghcup install cabal"),
+        ],
+        KnownLangId::Java => &[
+            tool!(
+                "java",
+                ["-version"],
+                Prefix,
+                "sdk install java {version}-tem"
+            ),
+            tool!("gradle", "sdk install gradle"),
+        ],
+        KnownLangId::Kotlin => &[
+            tool!("java", "sdk install java"),
+            tool!("gradle", "sdk install gradle"),
+        ],
+        KnownLangId::Scala => &[
+            tool!("java", "sdk install java"),
+            tool!("mill", "cs install mill"),
+            tool!("scalafmt", "cs install scalafmt"),
+        ],
+        KnownLangId::Coq => &[
+            tool!("coqc", ["--version"], Prefix, "opam install coq.{version}"),
+            tool!("coq_makefile", "opam install coq"),
+        ],
+        KnownLangId::TypeScript => &[
+            tool!("node", "fnm install --lts"),
+            tool!("pnpm", "npm install -g pnpm"),
+        ],
+        KnownLangId::Lean => &[tool!("lake", "elan toolchain install stable")],
+        KnownLangId::Agda => &[tool!("agda", "cabal install Agda")],
+        KnownLangId::Idris => &[tool!("idris", "cabal install idris")],
+        KnownLangId::CSharp | KnownLangId::FSharp => &[tool!(
+            "dotnet",
+            ["--version"],
+            AtLeast,
+            "dotnet-install.sh --version {version}"
+        )],
+        KnownLangId::Elixir => &[tool!("mix", "asdf install elixir latest")],
+        KnownLangId::Erlang => &[
+            tool!("erl", "asdf install erlang latest"),
+            tool!("rebar3", "asdf install rebar latest"),
+        ],
+        KnownLangId::Ruby | KnownLangId::Sql => &[
+            tool!("ruby", ["--version"], Prefix, "rbenv install {version}"),
+            tool!("bundle", "gem install bundler"),
+        ],
+        KnownLangId::Shell => &[
+            tool!("bash", "install bash with system package manager"),
+            tool!("ruby", "rbenv install"),
+            tool!("bundle", "gem install bundler"),
+        ],
+        KnownLangId::Nasm => &[
+            tool!("nasm", "install nasm with system package manager"),
+            tool!("cc", "install gcc with system package manager"),
+            tool!("make", "install make with system package manager"),
+        ],
+        KnownLangId::RiscV => &[
+            tool!("riscv64-linux-gnu-gcc", "install gcc-riscv64-linux-gnu"),
+            tool!("qemu-riscv64", "install qemu-user"),
+            tool!("make", "install make with system package manager"),
+        ],
+        KnownLangId::Clojure => &[tool!("clojure", "brew install clojure/tools/clojure")],
+        KnownLangId::Racket => &[tool!("raco", "install racket from https://racket-lang.org")],
+        KnownLangId::PureScript => &[
+            tool!("purs", "npm install -g purescript"),
+            tool!("spago", "npm install -g spago"),
+        ],
+        KnownLangId::Elm => &[
+            tool!("elm", ["--version"], Prefix, "npm install -g elm@{version}"),
+            tool!("elm-test", "npm install -g elm-test"),
+        ],
+        KnownLangId::Swift => &[tool!(
+            "swift",
+            ["--version"],
+            Prefix,
+            "swiftly install {version}"
+        )],
+        KnownLangId::Dart => &[tool!("dart", "install dart sdk from https://dart.dev")],
+        KnownLangId::Julia => &[tool!(
+            "julia",
+            ["--version"],
+            Prefix,
+            "juliaup add {version}"
+        )],
+        KnownLangId::R => &[tool!(
+            "Rscript",
+            "install r-base with system package manager"
+        )],
+        KnownLangId::Lua => &[tool!("busted", "luarocks install busted")],
+        KnownLangId::Nim => &[
+            tool!("nim", ["--version"], Prefix, "choosenim {version}"),
+            tool!("nimble", "choosenim stable"),
+        ],
+        KnownLangId::Crystal => &[
+            tool!(
+                "crystal",
+                ["--version"],
+                Prefix,
+                "install crystal {version} from https://crystal-lang.org"
+            ),
+            tool!("shards", "install crystal from https://crystal-lang.org"),
+        ],
+        KnownLangId::D => &[tool!("dub", "install dmd or ldc from https://dlang.org")],
+        KnownLangId::Prolog => &[tool!("swipl", "install swi-prolog")],
+        KnownLangId::CommonLisp => &[tool!("sbcl", "install sbcl")],
+        KnownLangId::Fortran => &[
+            tool!("gfortran", "install gfortran with system package manager"),
+            tool!("fpm", "pip install fpm"),
+        ],
+        _ => &[],
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Status {
+    Missing,
+    Found,
+    /// version could not be determined
+    UnknownVersion,
+    Mismatch {
+        found: String,
+    },
+}
+
+#[derive(Debug)]
+pub struct Report {
+    pub tool: &'static Tool,
+    pub status: Status,
+    pub expected: String,
+}
+impl Report {
+    pub fn is_ok(&self) -> bool {
+        matches!(self.status, Status::Found | Status::UnknownVersion)
+    }
+    /// install suggestion for missing or mismatched tool
+    pub fn suggestion(&self) -> String {
+        self.tool.install.replace("{version}", &self.expected)
+    }
+}
+impl Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.status {
+            Status::Found => write!(f, "{}: ok", self.tool.program),
+            Status::UnknownVersion => write!(f, "{}: unknown version", self.tool.program),
+            Status::Missing => write!(
+                f,
+                "{}: not found, try `{}`",
+                self.tool.program,
+                self.suggestion()
+            ),
+            Status::Mismatch { found } => write!(
+                f,
+                "{}: found version {found}, expected {}, try `{}`",
+                self.tool.program,
+                self.expected,
+                self.suggestion()
+            ),
+        }
+    }
+}
+
+fn find_program(program: &str) -> Option<PathBuf> {
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|p| p.join(program))
+        .find(|p| {
+            p.metadata()
+                .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        })
+}
+
+/// first `x.y[.z]` like word in tool output
+fn parse_version(output: &str) -> Option<&str> {
+    output
+        .split(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .map(|w| w.trim_matches('.'))
+        .find(|w| w.contains('.') && !w.starts_with('.'))
+}
+
+fn version_parts(v: &str) -> Vec<u64> {
+    v.split('.').map_while(|p| p.parse().ok()).collect()
+}
+
+fn version_matches(check: VersionCheck, found: &str, expected: &str) -> bool {
+    match check {
+        VersionCheck::None => true,
+        VersionCheck::Prefix => {
+            let (found, expected) = (version_parts(found), version_parts(expected));
+            found.starts_with(&expected)
+        }
+        VersionCheck::AtLeast => version_parts(found) >= version_parts(expected),
+    }
+}
+
+fn tool_version(path: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new(path)
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .ok()?;
+    let text = [output.stdout, output.stderr].concat();
+    parse_version(&String::from_utf8_lossy(&text)).map(str::to_string)
+}
+
+/// Probe tools of `lang` against local toolchain version of Codewars `version_id`
+pub fn probe(lang: KnownLangId, version_id: &str) -> Vec<Report> {
+    let expected = catalog::toolchain(lang, version_id).map_or(version_id, |t| t.version);
+    tools(lang)
+        .iter()
+        .map(|tool| {
+            let status = match find_program(tool.program) {
+                None => Status::Missing,
+                Some(_) if tool.check == VersionCheck::None => Status::Found,
+                Some(path) => match tool_version(&path, tool.version_args) {
+                    None => Status::UnknownVersion,
+                    Some(v) if version_matches(tool.check, &v, expected) => Status::Found,
+                    Some(found) => Status::Mismatch { found },
+                },
+            };
+            Report {
+                tool,
+                status,
+                expected: expected.to_string(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_version, version_matches, VersionCheck};
+
+    #[test]
+    fn version() {
+        assert_eq!(
+            parse_version("rustc 1.79.0 (129f3b996 2024-06-10)"),
+            Some("1.79.0")
+        );
+        assert_eq!(
+            parse_version("The Coq Proof Assistant, version 8.18.0\ncompiled with OCaml 4.14.1"),
+            Some("8.18.0")
+        );
+        assert_eq!(parse_version("no version"), None);
+    }
+
+    #[test]
+    fn matches() {
+        assert!(version_matches(VersionCheck::AtLeast, "1.79.0", "1.62"));
+        assert!(!version_matches(VersionCheck::AtLeast, "1.60.0", "1.62"));
+        assert!(version_matches(VersionCheck::Prefix, "0.19.1", "0.19.1"));
+        assert!(!version_matches(VersionCheck::Prefix, "8.18.0", "8.1"));
+    }
+}
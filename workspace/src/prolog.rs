@@ -0,0 +1,59 @@
+use std::{ffi::CStr, io, path::Path};
+
+use rustix::fd::{AsFd, OwnedFd};
+
+use crate::{util::fs, Code, Config, WorkspaceObject};
+
+const PRELOADED_FILE: &CStr = c"preloaded.pl";
+const CODE_FILE: &CStr = c"solution.pl";
+// plunit loads `<file>.plt` as tests of `<file>.pl`
+const FIXTURE_FILE: &CStr = c"solution.plt";
+
+pub struct Prolog {
+    root: OwnedFd,
+}
+impl Prolog {
+    pub fn create(root: &Path, project: Config) -> io::Result<Self> {
+        let root = fs::open_dirfd(root)?;
+
+        fs::write(root.as_fd(), CODE_FILE, project.code)?;
+        fs::write(root.as_fd(), FIXTURE_FILE, project.fixture)?;
+        if project.has_preload {
+            fs::write(root.as_fd(), PRELOADED_FILE, "")?;
+        }
+        fs::write(
+            root.as_fd(),
+            c"run_tests.pl",
+            format!(
+                include_str!("./prolog/run_tests.pl"),
+                preloaded = if project.has_preload {
+                    ":- consult(preloaded).\n"
+                } else {
+                    ""
+                }
+            ),
+        )?;
+        fs::write(root.as_fd(), c"Makefile", include_str!("./prolog/Makefile"))?;
+
+        Ok(Self { root })
+    }
+    pub fn open(root: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self {
+            root: fs::open_dirfd(root.as_ref())?,
+        })
+    }
+}
+impl WorkspaceObject for Prolog {
+    fn get_code(&self) -> Result<Code, io::Error> {
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), CODE_FILE)?,
+            fixture: fs::read_to_string(self.root.as_fd(), FIXTURE_FILE)?,
+        })
+    }
+    fn clean_build(&self) -> Result<(), io::Error> {
+        Ok(())
+    }
+    fn clean_session(&self) -> Result<(), io::Error> {
+        Ok(())
+    }
+}
@@ -0,0 +1,7 @@
+:- initialization(main, main).
+
+:- use_module(library(plunit)).
+{preloaded}:- consult(solution).
+:- load_test_files([]).
+
+main :- run_tests.
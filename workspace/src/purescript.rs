@@ -0,0 +1,149 @@
+use std::{
+    ffi::{CStr, CString},
+    io,
+    path::Path,
+};
+
+use codewars_types::KnownLangId;
+use rustix::{
+    fd::{AsFd, OwnedFd},
+    io::Errno,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    haskell::parse_header,
+    util::{fs, package_name, toolchain, write_module},
+    Code, Config, SubstitutedToolchain, WorkspaceObject,
+};
+
+const STATE_FILE: &CStr = c"purescript_state.json";
+const PRELOADED_PATH: &CStr = c"src/Preloaded.purs";
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    code_path: CString,
+    fixture_path: CString,
+}
+
+#[derive(Debug, thiserror::Error)]
+enum OpenErrInner {
+    #[error("failed to open state")]
+    Io(#[source] Errno),
+    #[error("failed to deserialize json")]
+    Json(#[source] serde_json::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct OpenError(#[from] OpenErrInner);
+
+#[derive(Debug, thiserror::Error)]
+enum CreateErrorInner {
+    #[error("unknown code {0}")]
+    UnknownCode(String),
+    #[error("unknown test {0}")]
+    UnknownTest(String),
+    #[error("failed to write source code")]
+    WriteCode(#[source] io::Error),
+    #[error("io error")]
+    Io(#[source] Errno),
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct CreateError(#[from] CreateErrorInner);
+
+pub struct PureScript {
+    root: OwnedFd,
+    state: State,
+}
+
+impl PureScript {
+    pub fn open(root: &Path) -> Result<Self, OpenError> {
+        let root = fs::open_dirfd(root).map_err(OpenErrInner::Io)?;
+        Ok(Self {
+            state: serde_json::from_slice(
+                &fs::read(root.as_fd(), STATE_FILE).map_err(OpenErrInner::Io)?,
+            )
+            .map_err(OpenErrInner::Json)?,
+            root,
+        })
+    }
+    pub fn create(
+        root: &Path,
+        project: Config,
+    ) -> Result<(Self, Option<SubstitutedToolchain>), CreateError> {
+        let (toolchain, substituted) = toolchain(KnownLangId::PureScript, project.version_id);
+        let root = fs::open_dirfd(root).map_err(CreateErrorInner::Io)?;
+
+        let code_mod = parse_header(project.code)
+            .module
+            .ok_or_else(|| CreateErrorInner::UnknownCode(project.code.to_string()))?;
+        let code_path = write_module(root.as_fd(), "src", &code_mod, "purs", project.code)
+            .map_err(CreateErrorInner::WriteCode)?;
+
+        if project.has_preload {
+            fs::write(root.as_fd(), PRELOADED_PATH, "module Preloaded where\n")
+                .map_err(CreateErrorInner::Io)?;
+        }
+
+        let test_mod = parse_header(project.fixture)
+            .module
+            .ok_or_else(|| CreateErrorInner::UnknownTest(project.fixture.to_string()))?;
+        let fixture_path = write_module(root.as_fd(), "test", &test_mod, "purs", project.fixture)
+            .map_err(CreateErrorInner::WriteCode)?;
+
+        fs::write(
+            root.as_fd(),
+            c"test/Main.purs",
+            format!(
+                include_str!("./purescript/Test_Main.purs"),
+                test_module = test_mod
+            ),
+        )
+        .map_err(CreateErrorInner::Io)?;
+
+        fs::write(
+            root.as_fd(),
+            c"spago.yaml",
+            format!(
+                include_str!("./purescript/spago.yaml"),
+                package = package_name(project.slug).replace('_', "-"),
+                registry = toolchain.option("registry").unwrap_or("60.0.0")
+            ),
+        )
+        .map_err(CreateErrorInner::Io)?;
+
+        let state = State {
+            code_path,
+            fixture_path,
+        };
+        fs::write(
+            root.as_fd(),
+            STATE_FILE,
+            serde_json::to_vec(&state).unwrap(),
+        )
+        .map_err(CreateErrorInner::Io)?;
+
+        Ok((Self { root, state }, substituted))
+    }
+}
+impl WorkspaceObject for PureScript {
+    fn get_code(&self) -> Result<Code, io::Error> {
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), &self.state.code_path)?,
+            fixture: fs::read_to_string(self.root.as_fd(), &self.state.fixture_path)?,
+        })
+    }
+    fn clean_build(&self) -> Result<(), io::Error> {
+        fs::remove_dir_all_at(self.root.as_fd(), c"output")?;
+        Ok(())
+    }
+    fn clean_session(&self) -> Result<(), io::Error> {
+        fs::remove_dir_all_at(self.root.as_fd(), c".spago")?;
+        fs::remove_at(self.root.as_fd(), c"spago.lock")?;
+        fs::remove_at(self.root.as_fd(), STATE_FILE)?;
+        Ok(())
+    }
+}
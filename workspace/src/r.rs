@@ -0,0 +1,73 @@
+use std::{ffi::CStr, path::Path};
+
+use rustix::{
+    fd::{AsFd, OwnedFd},
+    io::Errno,
+};
+
+use crate::{util::fs, Code, Config, WorkspaceObject};
+
+const PRELOADED_PATH: &CStr = c"preloaded.R";
+const CODE_PATH: &CStr = c"solution.R";
+const TEST_PATH: &CStr = c"tests/sample.R";
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct OpenError(Errno);
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct CreateError(#[from] Errno);
+
+pub struct R {
+    root: OwnedFd,
+}
+impl R {
+    pub fn open(root: &Path) -> Result<Self, OpenError> {
+        Ok(Self {
+            root: fs::open_dirfd(root).map_err(OpenError)?,
+        })
+    }
+    pub fn create(root: &Path, project: Config) -> Result<Self, CreateError> {
+        let root = fs::open_dirfd(root)?;
+
+        fs::write(root.as_fd(), CODE_PATH, project.code)?;
+        if project.has_preload {
+            fs::write(root.as_fd(), PRELOADED_PATH, "")?;
+        }
+
+        fs::mkdirat(root.as_fd(), c"tests")?;
+        fs::write(root.as_fd(), TEST_PATH, project.fixture)?;
+        fs::write(
+            root.as_fd(),
+            c"run_tests.R",
+            format!(
+                include_str!("./r/run_tests.R"),
+                preloaded = if project.has_preload {
+                    "source(\"preloaded.R\")\n"
+                } else {
+                    ""
+                }
+            ),
+        )?;
+
+        Ok(Self { root })
+    }
+}
+impl WorkspaceObject for R {
+    fn get_code(&self) -> Result<Code, std::io::Error> {
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), CODE_PATH)?,
+            fixture: fs::read_to_string(self.root.as_fd(), TEST_PATH)?,
+        })
+    }
+    fn clean_build(&self) -> Result<(), std::io::Error> {
+        Ok(())
+    }
+    fn clean_session(&self) -> Result<(), std::io::Error> {
+        fs::remove_at(self.root.as_fd(), c".RData")?;
+        fs::remove_at(self.root.as_fd(), c".Rhistory")?;
+        fs::remove_dir_all_at(self.root.as_fd(), c".Rproj.user")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,209 @@
+use std::{
+    ffi::{CStr, CString},
+    io,
+    path::Path,
+};
+
+use codewars_types::KnownLangId;
+use rustix::{
+    fd::{AsFd, OwnedFd},
+    io::Errno,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    util::{fs, package_name, toolchain},
+    Code, Config, SubstitutedToolchain, WorkspaceObject,
+};
+
+const STATE_FILE: &CStr = c"racket_state.json";
+const FIXTURE_PATH: &CStr = c"tests.rkt";
+const PRELOADED_PATH: &CStr = c"preloaded.rkt";
+const DEFAULT_CODE_PATH: &str = "solution.rkt";
+
+/// get the first local `.rkt` module other than preloaded required by fixture
+fn required_module(fixture: &str) -> Option<&str> {
+    fixture.match_indices("(require").find_map(|(idx, _)| {
+        let mut form = &fixture[idx + "(require".len()..];
+        let mut depth = 0;
+        loop {
+            let pos = form.find(['(', ')', '"', ';'])?;
+            match form.as_bytes()[pos] {
+                b'(' => depth += 1,
+                b')' if depth == 0 => return None,
+                b')' => depth -= 1,
+                b';' => {
+                    form = form[pos..].split_once('\n')?.1;
+                    continue;
+                }
+                _ => {
+                    let (path, rest) = form[pos + 1..].split_once('"')?;
+                    let preloaded =
+                        path.trim_start_matches("./").as_bytes() == PRELOADED_PATH.to_bytes();
+                    if path.ends_with(".rkt") && !preloaded {
+                        return Some(path);
+                    }
+                    form = rest;
+                    continue;
+                }
+            }
+            form = &form[pos + 1..];
+        }
+    })
+}
+
+#[derive(Serialize, Deserialize)]
+struct State {
+    code_path: CString,
+}
+
+#[derive(Debug, thiserror::Error)]
+enum OpenErrInner {
+    #[error("failed to open state")]
+    Io(#[source] Errno),
+    #[error("failed to deserialize json")]
+    Json(#[source] serde_json::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct OpenError(#[from] OpenErrInner);
+
+#[derive(Debug, thiserror::Error)]
+enum CreateErrorInner {
+    #[error("solution path {0} is outside of workspace")]
+    InvalidPath(String),
+    #[error("failed to write source code")]
+    WriteCode(#[source] io::Error),
+    #[error("io error")]
+    Io(#[source] Errno),
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct CreateError(#[from] CreateErrorInner);
+
+pub struct Racket {
+    root: OwnedFd,
+    state: State,
+}
+
+impl Racket {
+    pub fn open(root: &Path) -> Result<Self, OpenError> {
+        let root = fs::open_dirfd(root).map_err(OpenErrInner::Io)?;
+        Ok(Self {
+            state: serde_json::from_slice(
+                &fs::read(root.as_fd(), STATE_FILE).map_err(OpenErrInner::Io)?,
+            )
+            .map_err(OpenErrInner::Json)?,
+            root,
+        })
+    }
+    pub fn create(
+        root: &Path,
+        project: Config,
+    ) -> Result<(Self, Option<SubstitutedToolchain>), CreateError> {
+        let (toolchain, substituted) = toolchain(KnownLangId::Racket, project.version_id);
+        let root = fs::open_dirfd(root).map_err(CreateErrorInner::Io)?;
+
+        let code_path = required_module(project.fixture).unwrap_or(DEFAULT_CODE_PATH);
+        if code_path.starts_with('/') || code_path.split('/').any(|p| p == "..") {
+            return Err(CreateErrorInner::InvalidPath(code_path.to_string()).into());
+        }
+        fs::mkdir_all_at(
+            root.as_fd(),
+            Path::new(code_path).parent().unwrap_or(Path::new("")),
+        )
+        .map_err(CreateErrorInner::WriteCode)?;
+        let code_path = CString::new(code_path).map_err(|e| {
+            CreateErrorInner::WriteCode(io::Error::new(io::ErrorKind::InvalidInput, e))
+        })?;
+        fs::write(root.as_fd(), &code_path, project.code).map_err(CreateErrorInner::Io)?;
+
+        fs::write(root.as_fd(), FIXTURE_PATH, project.fixture).map_err(CreateErrorInner::Io)?;
+        if project.has_preload {
+            fs::write(
+                root.as_fd(),
+                PRELOADED_PATH,
+                "#lang racket\n(provide (all-defined-out))\n",
+            )
+            .map_err(CreateErrorInner::Io)?;
+        }
+
+        fs::write(
+            root.as_fd(),
+            c"info.rkt",
+            format!(
+                include_str!("./racket/info.rkt"),
+                package = package_name(project.slug),
+                racket_version = toolchain.version
+            ),
+        )
+        .map_err(CreateErrorInner::Io)?;
+
+        let state = State { code_path };
+        fs::write(
+            root.as_fd(),
+            STATE_FILE,
+            serde_json::to_vec(&state).unwrap(),
+        )
+        .map_err(CreateErrorInner::Io)?;
+
+        Ok((Self { root, state }, substituted))
+    }
+}
+impl WorkspaceObject for Racket {
+    fn get_code(&self) -> Result<Code, io::Error> {
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), &self.state.code_path)?,
+            fixture: fs::read_to_string(self.root.as_fd(), FIXTURE_PATH)?,
+        })
+    }
+    fn clean_build(&self) -> Result<(), io::Error> {
+        fs::remove_dir_all_at(self.root.as_fd(), c"compiled")?;
+        Ok(())
+    }
+    fn clean_session(&self) -> Result<(), io::Error> {
+        fs::remove_at(self.root.as_fd(), STATE_FILE)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::required_module;
+
+    #[test]
+    fn simple_require() {
+        assert_eq!(
+            required_module("#lang racket\n(require rackunit \"solution.rkt\")"),
+            Some("solution.rkt")
+        );
+    }
+
+    #[test]
+    fn nested_require() {
+        assert_eq!(
+            required_module(
+                "#lang racket\n\
+                (require rackunit)\n\
+                (require (only-in \"kata.rkt\" f) ; \"comment.rkt\"\n)"
+            ),
+            Some("kata.rkt")
+        );
+        assert_eq!(required_module("(require rackunit)"), None);
+    }
+
+    #[test]
+    fn skip_preloaded() {
+        assert_eq!(
+            required_module(
+                "#lang racket
+                (require \"preloaded.rkt\")
+                (require rackunit \"./preloaded.rkt\" \"kata.rkt\")"
+            ),
+            Some("kata.rkt")
+        );
+        assert_eq!(required_module("(require \"preloaded.rkt\")"), None);
+    }
+}
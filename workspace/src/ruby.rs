@@ -0,0 +1,97 @@
+use std::{ffi::CStr, path::Path};
+
+use codewars_types::KnownLangId;
+use rustix::{
+    fd::{AsFd, OwnedFd},
+    io::Errno,
+};
+
+use crate::{
+    util::{fs, toolchain},
+    Code, Config, SubstitutedToolchain, WorkspaceObject,
+};
+
+const PRELOADED_PATH: &CStr = c"preloaded.rb";
+const CODE_PATH: &CStr = c"solution.rb";
+const TEST_PATH: &CStr = c"spec/sample_spec.rb";
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct OpenError(Errno);
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct CreateError(#[from] Errno);
+
+pub struct Ruby {
+    root: OwnedFd,
+}
+impl Ruby {
+    pub fn open(root: &Path) -> Result<Self, OpenError> {
+        Ok(Self {
+            root: fs::open_dirfd(root).map_err(OpenError)?,
+        })
+    }
+    pub fn create(
+        root: &Path,
+        project: Config,
+    ) -> Result<(Self, Option<SubstitutedToolchain>), CreateError> {
+        let (toolchain, substituted) = toolchain(KnownLangId::Ruby, project.version_id);
+        let root = fs::open_dirfd(root)?;
+
+        fs::write(
+            root.as_fd(),
+            c"Gemfile",
+            format!(
+                include_str!("./ruby/Gemfile.in"),
+                ruby_version = toolchain.version
+            ),
+        )?;
+        fs::write(root.as_fd(), c".rspec", include_str!("./ruby/rspec"))?;
+
+        fs::write(root.as_fd(), CODE_PATH, project.code)?;
+        if project.has_preload {
+            fs::write(root.as_fd(), PRELOADED_PATH, "")?;
+        }
+
+        fs::mkdirat(root.as_fd(), c"spec")?;
+        fs::write(root.as_fd(), TEST_PATH, project.fixture)?;
+        fs::write(
+            root.as_fd(),
+            c"spec/spec_helper.rb",
+            format!(
+                include_str!("./ruby/spec_helper.rb.in"),
+                preloaded = if project.has_preload {
+                    "require_relative \"../preloaded\"\n"
+                } else {
+                    ""
+                }
+            ),
+        )?;
+
+        fs::mkdirat(root.as_fd(), c"spec/support")?;
+        fs::write(
+            root.as_fd(),
+            c"spec/support/codewars_test.rb",
+            include_str!("./ruby/codewars_test.rb"),
+        )?;
+
+        Ok((Self { root }, substituted))
+    }
+}
+impl WorkspaceObject for Ruby {
+    fn get_code(&self) -> Result<Code, std::io::Error> {
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), CODE_PATH)?,
+            fixture: fs::read_to_string(self.root.as_fd(), TEST_PATH)?,
+        })
+    }
+    fn clean_build(&self) -> Result<(), std::io::Error> {
+        Ok(())
+    }
+    fn clean_session(&self) -> Result<(), std::io::Error> {
+        fs::remove_dir_all_at(self.root.as_fd(), c".bundle")?;
+        fs::remove_at(self.root.as_fd(), c"Gemfile.lock")?;
+        Ok(())
+    }
+}
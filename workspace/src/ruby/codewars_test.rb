@@ -0,0 +1,83 @@
+# Legacy Codewars `Test` API implemented on top of RSpec.
+#
+# Assertions made inside an example are checked immediately. Assertions made
+# outside of any example (common in old fixtures) are registered as examples
+# of their own, so a failing one does not abort loading the rest of the file.
+require "rspec/core"
+require "rspec/expectations"
+
+module Test
+  @groups = []
+
+  class << self
+    def describe(message, &block)
+      parent = @groups.last || RSpec
+      parent.describe(message) do
+        Test.send(:enter, self, &block)
+      end
+    end
+
+    def it(message, &block)
+      group = @groups.last
+      if group
+        group.it(message, &block)
+      else
+        RSpec.describe("Test") { it(message, &block) }
+      end
+    end
+
+    def expect(passed = nil, message = nil, _options = {})
+      report(passed, message || "Value is not what was expected")
+    end
+
+    def assert_equals(actual, expected, message = nil, _options = {})
+      report(
+        actual == expected,
+        message || "Expected: #{expected.inspect}, instead got: #{actual.inspect}"
+      )
+    end
+
+    def assert_not_equals(actual, expected, message = nil, _options = {})
+      report(
+        actual != expected,
+        message || "Expected #{actual.inspect} not to equal #{expected.inspect}"
+      )
+    end
+
+    def expect_error(message = nil)
+      begin
+        yield
+      rescue StandardError
+        return report(true, message)
+      end
+      report(false, message || "Expected an error to be raised")
+    end
+
+    def expect_no_error(message = nil)
+      begin
+        yield
+      rescue StandardError => e
+        return report(false, message || "Expected no error, got #{e.inspect}")
+      end
+      report(true, message)
+    end
+
+    private
+
+    def enter(group, &block)
+      @groups.push(group)
+      group.module_exec(&block)
+    ensure
+      @groups.pop
+    end
+
+    def report(passed, message)
+      check = proc { RSpec::Expectations.fail_with(message) unless passed }
+      if RSpec.current_example
+        check.call
+      else
+        it(message || "assertion", &check)
+      end
+    end
+  end
+end
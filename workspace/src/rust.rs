@@ -1,24 +1,212 @@
 use std::{borrow::Cow, ffi::CStr, io, path::Path};
 
+use codewars_types::{catalog::Toolchain, KnownLangId};
 use rustix::{
     fd::{AsFd, OwnedFd},
     io::Errno,
 };
 
 use crate::{
-    util::{call_command_at, fs},
-    Code, Config, WorkspaceObject,
+    util::{build_timeout, call_command_at, fs, toolchain},
+    Code, Config, SubstitutedToolchain, WorkspaceObject,
 };
 
 #[derive(Debug, thiserror::Error)]
 #[error(transparent)]
 pub struct Error(#[from] Errno);
 
+/// Crate used by solution or fixture but not provided by Codewars runner
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("crate {name} is not available on codewars rust {version_id}")]
+pub struct UnavailableCrate {
+    pub name: String,
+    pub version_id: String,
+}
+
 pub struct Rust {
     root: OwnedFd,
 }
+/// features enabled by codewars runner for provided crates
+const FEATURES: &[(&str, &str)] = &[
+    ("num", "rand"),
+    ("serde", "derive"),
+    ("tokio", "full"),
+    ("tokio-util", "full"),
+];
+
+/// cargo manifest table `[header]` of `(crate, version)` packages
+fn dependency_table(header: &str, packages: &[(&str, &str)]) -> String {
+    let mut ret = format!("\n[{header}]\n");
+    for (name, version) in packages {
+        match FEATURES.iter().find(|(n, _)| n == name) {
+            Some((_, feature)) => ret.push_str(&format!(
+                "{name} = {{ version = \"{version}\", features = [\"{feature}\"] }}\n"
+            )),
+            None => ret.push_str(&format!("{name} = \"{version}\"\n")),
+        }
+    }
+    ret
+}
+
+/// crate names provided by toolchain, including test only crates if `dev`
+fn provided_crates(toolchain: &Toolchain, dev: bool) -> Vec<String> {
+    let test_packages = if dev { toolchain.test_packages } else { &[] };
+    toolchain
+        .packages
+        .iter()
+        .chain(test_packages)
+        .map(|(name, _)| name.replace('-', "_"))
+        .collect()
+}
+
+/// item with leading attributes and visibility stripped
+fn strip_item(item: &str) -> &str {
+    let mut item = item.trim_start();
+    while let Some((_, rest)) = item
+        .strip_prefix("#[")
+        .and_then(|attr| attr.split_once(']'))
+    {
+        item = rest.trim_start();
+    }
+    match item.strip_prefix("pub") {
+        Some(rest) if rest.starts_with('(') => rest
+            .split_once(')')
+            .map_or(item, |(_, rest)| rest.trim_start()),
+        Some(rest) if rest.starts_with(char::is_whitespace) => rest.trim_start(),
+        _ => item,
+    }
+}
+
+/// items of `code` with attributes and visibility stripped
+///
+/// Items are recognized at line start and after `{`, `}` or `;` on the same line.
+fn items(code: &str) -> impl Iterator<Item = &str> {
+    code.lines()
+        .flat_map(|l| l.split(['{', '}', ';']))
+        .map(strip_item)
+}
+
+/// leading identifier of `s`
+fn ident(s: &str) -> &str {
+    let end = s
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(s.len());
+    &s[..end]
+}
+
+/// names of modules and types declared by `code`
+fn declared_names(code: &str) -> Vec<&str> {
+    items(code)
+        .filter_map(|item| {
+            ["mod ", "enum ", "struct ", "trait ", "type ", "union "]
+                .iter()
+                .find_map(|kw| item.strip_prefix(kw))
+        })
+        .map(|rest| ident(rest.trim_start()))
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// external crates referred by `use` and `extern crate` items, except names in `local`
+fn used_crates<'a>(code: &'a str, local: &[&str]) -> Vec<&'a str> {
+    let mut ret = Vec::new();
+    for item in items(code) {
+        let path = match item
+            .strip_prefix("use ")
+            .or_else(|| item.strip_prefix("extern crate "))
+        {
+            Some(p) => p.trim_start().trim_start_matches("::"),
+            None => continue,
+        };
+        let name = ident(path);
+        if !(name.is_empty()
+            || matches!(
+                name,
+                "std" | "core" | "alloc" | "crate" | "self" | "super" | "preloaded"
+            )
+            || local.contains(&name)
+            || ret.contains(&name))
+        {
+            ret.push(name);
+        }
+    }
+    ret
+}
+
+/// whether fixture is a `mod tests { use super::*; ... }` meant to be appended to solution
+fn is_inline_fixture(fixture: &str) -> bool {
+    let mut depth = 0usize;
+    // top level `mod name` seen, body not opened yet
+    let mut mod_head = false;
+    let mut in_mod = false;
+    for part in fixture
+        .lines()
+        .flat_map(|l| l.split_inclusive(['{', '}', ';']))
+    {
+        let item = strip_item(part.trim_end_matches(['{', '}', ';']));
+        if depth == 0 && item.starts_with("mod ") {
+            mod_head = true;
+        } else if in_mod && item.starts_with("use super::") {
+            return true;
+        } else if !item.trim().is_empty() {
+            mod_head = false;
+        }
+        match part.chars().last() {
+            Some('{') => {
+                depth += 1;
+                in_mod |= depth == 1 && mod_head;
+                mod_head = false;
+            }
+            Some('}') => {
+                depth = depth.saturating_sub(1);
+                in_mod &= depth > 0;
+            }
+            Some(';') => mod_head = false,
+            _ => (),
+        }
+    }
+    false
+}
+
 const CODE_PATH: &CStr = c"src/lib.rs";
+const PRELOADED_PATH: &CStr = c"src/preloaded.rs";
 const TEST_PATH: &CStr = c"tests/sample.rs";
+/// inline fixture, outside `tests` so cargo does not build it as integration test
+const INLINE_TEST_PATH: &CStr = c"src/sample_tests.rs";
+/// appended to solution to include inline fixture
+const INLINE_TEST_INCLUDE: &str = "include!(\"sample_tests.rs\");";
+
+/// solution without the inline fixture include and its `#[cfg(test)]`, wherever it was moved to
+fn strip_inline_include(solution: &str) -> String {
+    let lines: Vec<_> = solution.lines().collect();
+    let is_include = |l: &str| {
+        l.trim()
+            .strip_suffix(INLINE_TEST_INCLUDE)
+            .is_some_and(|attr| matches!(attr.trim(), "" | "#[cfg(test)]"))
+    };
+    let Some(idx) = lines.iter().rposition(|l| is_include(l)) else {
+        return solution.to_string();
+    };
+    let mut start = idx;
+    if start > 0 && lines[start - 1].trim() == "#[cfg(test)]" {
+        start -= 1;
+    }
+    let rest = &lines[idx + 1..];
+    let rest = if rest.iter().all(|l| l.trim().is_empty()) {
+        // also drop blank line separating appended include from solution
+        while start > 0 && lines[start - 1].trim().is_empty() {
+            start -= 1;
+        }
+        &[]
+    } else {
+        rest
+    };
+    lines[..start]
+        .iter()
+        .chain(rest)
+        .map(|l| format!("{l}\n"))
+        .collect()
+}
 
 impl Rust {
     pub fn open(root: &Path) -> Result<Self, Error> {
@@ -27,11 +215,33 @@ impl Rust {
         })
     }
 
-    pub fn create(root: impl AsRef<Path>, project: Config) -> Result<Self, Error> {
+    /// Create workspace, also returning toolchain substituted for unknown version and crates
+    /// used by code but missing on Codewars
+    ///
+    /// Unknown versions keep their `rust-version` but get the crates of the newest toolchain.
+    pub fn create(
+        root: impl AsRef<Path>,
+        project: Config,
+    ) -> Result<(Self, Option<SubstitutedToolchain>, Vec<UnavailableCrate>), Error> {
+        let (toolchain, substituted) = toolchain(KnownLangId::Rust, project.version_id);
         let root = fs::open_dirfd(root.as_ref())?;
 
+        let inline = is_inline_fixture(project.fixture);
         fs::mkdirat(root.as_fd(), c"src")?;
-        fs::write(root.as_fd(), CODE_PATH, project.code)?;
+        if inline {
+            fs::write(
+                root.as_fd(),
+                CODE_PATH,
+                format!("{}\n#[cfg(test)]\n{INLINE_TEST_INCLUDE}\n", project.code),
+            )?;
+            fs::write(root.as_fd(), INLINE_TEST_PATH, project.fixture)?;
+        } else {
+            fs::write(root.as_fd(), CODE_PATH, project.code)?;
+        }
+        if project.has_preload {
+            // solution declares `mod preloaded;` like on codewars
+            fs::write(root.as_fd(), PRELOADED_PATH, "")?;
+        }
 
         let crate_name = if project.slug.starts_with(|c: char| !c.is_alphabetic()) {
             Cow::Owned(format!("cw-{}", project.slug))
@@ -40,18 +250,39 @@ impl Rust {
         };
         let crate_name_rs = crate_name.replace('-', "_");
 
-        fs::mkdirat(root.as_fd(), c"tests")?;
-        fs::write(
-            root.as_fd(),
-            TEST_PATH,
-            format!(
-                "#[cfg(feature = \"local\")]\n\
-                use {crate_name_rs}::*;\n\
-                \n\
-                {}",
-                project.fixture
-            ),
-        )?;
+        // fixture may refer to modules and types of solution, like through `use super::*`
+        let mut local = declared_names(project.code);
+        local.extend(declared_names(project.fixture));
+        local.push(&crate_name_rs);
+        let mut unavailable: Vec<UnavailableCrate> = Vec::new();
+        for (code, dev) in [(project.code, false), (project.fixture, true)] {
+            let provided = provided_crates(toolchain, dev);
+            for name in used_crates(code, &local) {
+                if !provided.iter().any(|c| c == name)
+                    && !unavailable.iter().any(|c| c.name == name)
+                {
+                    unavailable.push(UnavailableCrate {
+                        name: name.to_string(),
+                        version_id: project.version_id.to_string(),
+                    });
+                }
+            }
+        }
+
+        if !inline {
+            fs::mkdirat(root.as_fd(), c"tests")?;
+            fs::write(
+                root.as_fd(),
+                TEST_PATH,
+                format!(
+                    "#[cfg(feature = \"local\")]\n\
+                    use {crate_name_rs}::*;\n\
+                    \n\
+                    {}",
+                    project.fixture
+                ),
+            )?;
+        }
 
         fs::mkdirat(root.as_fd(), c".cargo")?;
         fs::write(
@@ -66,23 +297,38 @@ impl Rust {
             format!(
                 include_str!("./rust/Cargo.toml"),
                 crate_name = crate_name,
-                rust_version = project.version_id
+                rust_version = if substituted.is_some() {
+                    project.version_id
+                } else {
+                    toolchain.version
+                },
+                dependencies = dependency_table("dependencies", toolchain.packages)
+                    + &dependency_table("dev-dependencies", toolchain.test_packages)
             ),
         )?;
 
-        Ok(Self { root })
+        Ok((Self { root }, substituted, unavailable))
     }
 }
 
 impl WorkspaceObject for Rust {
     fn get_code(&self) -> Result<Code, io::Error> {
-        Ok(Code {
-            solution: fs::read_to_string(self.root.as_fd(), CODE_PATH)?,
-            fixture: fs::read_to_string(self.root.as_fd(), TEST_PATH)?,
-        })
+        let solution = fs::read_to_string(self.root.as_fd(), CODE_PATH)?;
+        match fs::read_to_string(self.root.as_fd(), INLINE_TEST_PATH) {
+            Ok(fixture) => Ok(Code {
+                solution: strip_inline_include(&solution),
+                fixture,
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Code {
+                solution,
+                fixture: fs::read_to_string(self.root.as_fd(), TEST_PATH)?,
+            }),
+            Err(e) => Err(e),
+        }
     }
     fn clean_build(&self) -> Result<(), io::Error> {
-        call_command_at(self.root.as_fd(), "cargo", ["clean"])
+        call_command_at(self.root.as_fd(), "cargo", ["clean"], build_timeout())?.check()?;
+        Ok(())
     }
     fn clean_session(&self) -> Result<(), io::Error> {
         fs::remove_dir_all_at(self.root.as_fd(), c".cargo")?;
@@ -90,3 +336,151 @@ impl WorkspaceObject for Rust {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use codewars_types::{catalog, KnownLangId};
+
+    use super::{
+        declared_names, dependency_table, is_inline_fixture, provided_crates, strip_inline_include,
+        used_crates,
+    };
+
+    #[test]
+    fn manifest() {
+        assert_eq!(
+            dependency_table("dependencies", &[("regex", "1.6.0"), ("num", "0.4.0")]),
+            "\n[dependencies]\n\
+            regex = \"1.6.0\"\n\
+            num = { version = \"0.4.0\", features = [\"rand\"] }\n"
+        );
+    }
+
+    #[test]
+    fn inline_fixture() {
+        assert!(is_inline_fixture(
+            "#[cfg(test)]\n\
+            mod tests {\n\
+            \x20   use super::*;\n\
+            \n\
+            \x20   #[test]\n\
+            \x20   fn sample() {}\n\
+            }"
+        ));
+        assert!(!is_inline_fixture(
+            "#[test]\n\
+            fn sample() {}\n\
+            mod helper {\n\
+            }\n\
+            use super::*;"
+        ));
+        assert!(!is_inline_fixture(
+            "mod helper;\n\
+            fn f() {\n\
+            \x20   use super::*;\n\
+            }"
+        ));
+    }
+
+    #[test]
+    fn inline_fixture_forms() {
+        // attribute on the same line
+        assert!(is_inline_fixture(
+            "#[cfg(test)] mod tests {\n\
+            \x20   use super::*;\n\
+            }"
+        ));
+        // public module
+        assert!(is_inline_fixture(
+            "pub mod tests {\n\
+            \x20   use super::*;\n\
+            }"
+        ));
+        // indented module
+        assert!(is_inline_fixture(
+            "  #[cfg(test)]\n\
+            \x20 mod tests {\n\
+            \x20     use super::*;\n\
+            \x20 }"
+        ));
+        // brace on next line
+        assert!(is_inline_fixture(
+            "#[cfg(test)]\n\
+            mod tests\n\
+            {\n\
+            \x20   use super::*;\n\
+            }"
+        ));
+        // everything on one line
+        assert!(is_inline_fixture(
+            "#[cfg(test)] mod tests { use super::*; #[test] fn f() {} }"
+        ));
+    }
+
+    #[test]
+    fn inline_include() {
+        let code = "fn f() {}\n";
+        let written = "fn f() {}\n\n#[cfg(test)]\ninclude!(\"sample_tests.rs\");\n";
+        assert_eq!(strip_inline_include(written), code);
+        // reformatted, moved or followed by new code
+        assert_eq!(
+            strip_inline_include("fn f() {}\n#[cfg(test)] include!(\"sample_tests.rs\");\n\n\n"),
+            code
+        );
+        assert_eq!(
+            strip_inline_include(
+                "use std::fmt;\n#[cfg(test)]\n  include!(\"sample_tests.rs\");\nfn f() {}\n"
+            ),
+            "use std::fmt;\nfn f() {}\n"
+        );
+        assert_eq!(strip_inline_include(code), code);
+    }
+
+    #[test]
+    fn crates() {
+        let toolchain = catalog::toolchain(KnownLangId::Rust, "1.62").unwrap();
+        let provided = provided_crates(toolchain, false);
+        assert!(provided.iter().any(|c| c == "fancy_regex"));
+        assert!(!provided.iter().any(|c| c == "quickcheck"));
+        assert!(provided_crates(toolchain, true)
+            .iter()
+            .any(|c| c == "quickcheck"));
+
+        assert_eq!(
+            used_crates(
+                "use std::collections::HashMap;\n\
+                use itertools::Itertools;\n\
+                pub use ::regex::Regex;\n\
+                extern crate rand;\n\
+                mod preloaded;\n\
+                use preloaded::Tree;\n\
+                fn f() { use super::*; }\n\
+                fn g() { use chrono::Utc; let x = 1; use either::Either; }\n\
+                mod m { pub use num::Integer; }",
+                &[]
+            ),
+            ["itertools", "regex", "rand", "chrono", "either", "num"]
+        );
+    }
+
+    #[test]
+    fn local_names() {
+        let code = "#[derive(Debug)] pub enum Direction { Up, Down }\n\
+            pub(crate) struct Point(i32, i32);\n\
+            mod helpers;\n\
+            pub mod geometry {\n\
+            \x20   pub trait Shape {}\n\
+            }\n\
+            type Grid = Vec<Vec<u8>>;\n\
+            use Direction::*;\n\
+            use helpers::f;\n\
+            pub(crate) use geometry::Shape;\n\
+            use itertools::Itertools;";
+        let local = declared_names(code);
+        assert_eq!(
+            local,
+            ["Direction", "Point", "helpers", "geometry", "Shape", "Grid"]
+        );
+        assert_eq!(used_crates(code, &local), ["itertools"]);
+    }
+}
@@ -0,0 +1,456 @@
+//! Optional Linux sandbox around commands run in workspace.
+//!
+//! Commands are isolated with user, mount, ipc and network namespaces and can not use
+//! syscalls useful to escape or inspect other processes. The whole filesystem is read
+//! only except the workspace, a private `/tmp` and configured writable directories.
+//! Test runs are additionally limited like on Codewars.
+//!
+//! Only commands the workspace runs itself through [`Runner`](crate::util::Runner) are
+//! sandboxed, i.e. clean commands and local tests. Builds and tests started by the user
+//! in the workspace directory, e.g. `cargo test`, run unrestricted.
+
+use std::{
+    ffi::CString,
+    io,
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
+    ptr,
+    sync::OnceLock,
+    time::Duration,
+};
+
+use rustix::fd::{AsRawFd, BorrowedFd};
+
+#[cfg(any(
+    all(target_arch = "x86_64", target_pointer_width = "64"),
+    target_arch = "aarch64"
+))]
+mod seccomp;
+#[cfg(not(any(
+    all(target_arch = "x86_64", target_pointer_width = "64"),
+    target_arch = "aarch64"
+)))]
+mod seccomp {
+    pub(super) fn filter() -> std::io::Result<Vec<libc::sock_filter>> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "sandbox unsupported on this architecture",
+        ))
+    }
+}
+
+/// Codewars stops test runs after 12 seconds
+pub const CODEWARS_TIMEOUT: u64 = 12;
+
+#[derive(Debug, Clone)]
+pub struct Sandbox {
+    /// cpu time limit of each test process in seconds
+    pub cpu_time: Option<u64>,
+    /// wall clock limit of test runs in seconds
+    pub wall_time: Option<u64>,
+    /// address space limit of each process in bytes
+    pub memory: Option<u64>,
+    /// keep network access
+    pub network: bool,
+    /// directories writable besides workspace and `/tmp`, e.g. build caches
+    pub writable: Vec<PathBuf>,
+}
+impl Default for Sandbox {
+    fn default() -> Self {
+        Self {
+            cpu_time: Some(CODEWARS_TIMEOUT),
+            wall_time: Some(CODEWARS_TIMEOUT),
+            memory: None,
+            network: false,
+            writable: Vec::new(),
+        }
+    }
+}
+impl Sandbox {
+    /// wall clock limit of test runs
+    pub(crate) fn test_timeout(&self) -> Option<Duration> {
+        self.wall_time.map(Duration::from_secs)
+    }
+}
+
+static SANDBOX: OnceLock<Sandbox> = OnceLock::new();
+
+/// Run all following workspace commands in `sandbox`, fails if already enabled
+pub fn enable(sandbox: Sandbox) -> Result<(), Sandbox> {
+    SANDBOX.set(sandbox)
+}
+
+pub(crate) fn current() -> Option<&'static Sandbox> {
+    SANDBOX.get()
+}
+
+// not exported by libc, see mount_setattr(2) and open_tree(2)
+const MOUNT_ATTR_RDONLY: u64 = 0x1;
+const OPEN_TREE_CLONE: libc::c_uint = 0x1;
+const MOVE_MOUNT_F_EMPTY_PATH: libc::c_uint = 0x4;
+#[repr(C)]
+struct MountAttr {
+    attr_set: u64,
+    attr_clr: u64,
+    propagation: u64,
+    userns_fd: u64,
+}
+
+/// Directory kept writable inside read only filesystem
+struct Writable {
+    path: CString,
+    /// directories to create in private `/tmp` before mounting
+    mkdirs: Vec<CString>,
+}
+
+/// Sandbox setup computed before fork, so child only issues syscalls
+pub(crate) struct Prepared {
+    unshare: libc::c_int,
+    uid_map: Vec<u8>,
+    gid_map: Vec<u8>,
+    /// workspace first
+    writable: Vec<Writable>,
+    /// fds of detached mount trees, capacity for every writable directory is reserved here
+    trees: Vec<libc::c_int>,
+    limits: Vec<(libc::__rlimit_resource_t, libc::rlimit)>,
+    filter: Vec<libc::sock_filter>,
+}
+
+fn c_path(p: &Path) -> io::Result<CString> {
+    CString::new(p.as_os_str().as_bytes())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+}
+
+impl Writable {
+    fn new(path: &Path) -> io::Result<Self> {
+        let path = path.canonicalize()?;
+        // `/tmp` is replaced by empty tmpfs, recreate the path inside it
+        let mkdirs = match path.strip_prefix("/tmp") {
+            Ok(rel) => {
+                let mut dirs = rel
+                    .ancestors()
+                    .filter(|a| !a.as_os_str().is_empty())
+                    .map(|a| c_path(&Path::new("/tmp").join(a)))
+                    .collect::<io::Result<Vec<_>>>()?;
+                dirs.reverse();
+                dirs
+            }
+            Err(_) => Vec::new(),
+        };
+        Ok(Self {
+            path: c_path(&path)?,
+            mkdirs,
+        })
+    }
+}
+
+impl Sandbox {
+    /// Prepare running a command in `workspace`, `test` runs get Codewars limits
+    pub(crate) fn prepare(&self, workspace: BorrowedFd, test: bool) -> io::Result<Prepared> {
+        let filter = seccomp::filter()?;
+
+        let mut unshare = libc::CLONE_NEWUSER | libc::CLONE_NEWNS | libc::CLONE_NEWIPC;
+        if !self.network {
+            unshare |= libc::CLONE_NEWNET;
+        }
+
+        let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+
+        let workspace = std::fs::read_link(format!("/proc/self/fd/{}", workspace.as_raw_fd()))?;
+        let writable = std::iter::once(workspace.as_path())
+            .chain(self.writable.iter().map(PathBuf::as_path))
+            .map(Writable::new)
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let mut limits = vec![(
+            libc::RLIMIT_CORE,
+            libc::rlimit {
+                rlim_cur: 0,
+                rlim_max: 0,
+            },
+        )];
+        if let Some(t) = self.cpu_time.filter(|_| test) {
+            // SIGXCPU at soft limit, SIGKILL one second later
+            limits.push((
+                libc::RLIMIT_CPU,
+                libc::rlimit {
+                    rlim_cur: t,
+                    rlim_max: t + 1,
+                },
+            ));
+        }
+        if let Some(m) = self.memory {
+            limits.push((
+                libc::RLIMIT_AS,
+                libc::rlimit {
+                    rlim_cur: m,
+                    rlim_max: m,
+                },
+            ));
+        }
+
+        Ok(Prepared {
+            unshare,
+            uid_map: format!("{uid} {uid} 1").into_bytes(),
+            gid_map: format!("{gid} {gid} 1").into_bytes(),
+            trees: Vec::with_capacity(writable.len()),
+            writable,
+            limits,
+            filter,
+        })
+    }
+}
+
+fn check(ret: libc::c_int) -> io::Result<()> {
+    if ret == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+fn write_proc(path: &std::ffi::CStr, data: &[u8]) -> io::Result<()> {
+    unsafe {
+        let fd = libc::open(path.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC);
+        check(fd)?;
+        let ret = libc::write(fd, data.as_ptr().cast(), data.len());
+        libc::close(fd);
+        if ret < 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+fn check_syscall(ret: libc::c_long) -> io::Result<libc::c_long> {
+    if ret == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(ret)
+    }
+}
+
+/// set and clear mount attributes of `path` relative to `dirfd`
+unsafe fn mount_setattr(
+    dirfd: libc::c_int,
+    path: &std::ffi::CStr,
+    flags: libc::c_int,
+    attr: MountAttr,
+) -> io::Result<()> {
+    check_syscall(libc::syscall(
+        libc::SYS_mount_setattr,
+        dirfd,
+        path.as_ptr(),
+        flags,
+        &attr as *const MountAttr,
+        std::mem::size_of::<MountAttr>(),
+    ))?;
+    Ok(())
+}
+
+impl Prepared {
+    /// Enter sandbox and change directory to workspace, called in child between fork and exec
+    pub(crate) fn apply(&mut self) -> io::Result<()> {
+        check(unsafe { libc::unshare(self.unshare) })?;
+        write_proc(c"/proc/self/setgroups", b"deny")?;
+        write_proc(c"/proc/self/uid_map", &self.uid_map)?;
+        write_proc(c"/proc/self/gid_map", &self.gid_map)?;
+
+        unsafe {
+            // keep mounts below from propagating to parent namespace
+            check(libc::mount(
+                ptr::null(),
+                c"/".as_ptr(),
+                ptr::null(),
+                libc::MS_REC | libc::MS_PRIVATE,
+                ptr::null(),
+            ))?;
+            mount_setattr(
+                libc::AT_FDCWD,
+                c"/",
+                libc::AT_RECURSIVE,
+                MountAttr {
+                    attr_set: MOUNT_ATTR_RDONLY,
+                    attr_clr: 0,
+                    propagation: 0,
+                    userns_fd: 0,
+                },
+            )?;
+
+            // detached writable copies of directories, mounted back after `/tmp` is replaced
+            // pushes stay within reserved capacity, the child must not allocate
+            self.trees.clear();
+            for w in &self.writable {
+                let fd = check_syscall(libc::syscall(
+                    libc::SYS_open_tree,
+                    libc::AT_FDCWD,
+                    w.path.as_ptr(),
+                    OPEN_TREE_CLONE
+                        | libc::O_CLOEXEC as libc::c_uint
+                        | libc::AT_RECURSIVE as libc::c_uint,
+                ))? as libc::c_int;
+                self.trees.push(fd);
+                mount_setattr(
+                    fd,
+                    c"",
+                    libc::AT_EMPTY_PATH,
+                    MountAttr {
+                        attr_set: 0,
+                        attr_clr: MOUNT_ATTR_RDONLY,
+                        propagation: 0,
+                        userns_fd: 0,
+                    },
+                )?;
+            }
+
+            check(libc::mount(
+                c"tmpfs".as_ptr(),
+                c"/tmp".as_ptr(),
+                c"tmpfs".as_ptr(),
+                libc::MS_NOSUID | libc::MS_NODEV,
+                c"mode=1777".as_ptr().cast(),
+            ))?;
+
+            for (w, &fd) in self.writable.iter().zip(&self.trees) {
+                for dir in &w.mkdirs {
+                    if libc::mkdir(dir.as_ptr(), 0o755) == -1
+                        && io::Error::last_os_error().raw_os_error() != Some(libc::EEXIST)
+                    {
+                        return Err(io::Error::last_os_error());
+                    }
+                }
+                check_syscall(libc::syscall(
+                    libc::SYS_move_mount,
+                    fd,
+                    c"".as_ptr(),
+                    libc::AT_FDCWD,
+                    w.path.as_ptr(),
+                    MOVE_MOUNT_F_EMPTY_PATH,
+                ))?;
+                libc::close(fd);
+            }
+            // previous working directory is on read only mount below workspace mount
+            check(libc::chdir(self.writable[0].path.as_ptr()))?;
+
+            for (resource, limit) in &self.limits {
+                check(libc::setrlimit(*resource, limit))?;
+            }
+
+            check(libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0))?;
+            let prog = libc::sock_fprog {
+                len: self.filter.len() as u16,
+                filter: self.filter.as_ptr().cast_mut(),
+            };
+            check(libc::prctl(
+                libc::PR_SET_SECCOMP,
+                libc::SECCOMP_MODE_FILTER,
+                &prog as *const libc::sock_fprog,
+            ))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        fs,
+        path::PathBuf,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use rustix::fd::AsFd;
+
+    use super::Sandbox;
+    use crate::util::{process::ExitStatus, Output, Runner};
+
+    /// new empty directory in temp dir of host
+    fn temp_dir() -> PathBuf {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "cw-sandbox-{}-{}",
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// run `script` in new workspace directory, `None` if user namespaces are unavailable
+    fn run(sandbox: &Sandbox, test: bool, script: &str) -> Option<(PathBuf, Output)> {
+        let dir = temp_dir();
+        let fd = rustix::fs::open(
+            &dir,
+            rustix::fs::OFlags::DIRECTORY,
+            rustix::fs::Mode::empty(),
+        )
+        .unwrap();
+        let ret = Runner::new(fd.as_fd(), "sh")
+            .args(["-c", script])
+            .interactive(false)
+            .capture(true)
+            .test(test)
+            .run_in(Some(sandbox));
+        match ret {
+            Ok(out) => Some((dir, out)),
+            Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+                eprintln!("skipping sandbox test: {e}");
+                fs::remove_dir_all(dir).unwrap();
+                None
+            }
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    fn stdout(out: &Output) -> &str {
+        std::str::from_utf8(&out.stdout).unwrap().trim()
+    }
+
+    #[test]
+    fn filesystem() {
+        let outside = temp_dir();
+        let script = format!(
+            "echo ws > ws_file; echo tmp > /tmp/cw_private; touch {}/cw_probe; echo $?",
+            outside.display()
+        );
+        let ret = run(&Sandbox::default(), false, &script);
+        assert!(!outside.join("cw_probe").exists());
+        fs::remove_dir_all(outside).unwrap();
+        let Some((dir, out)) = ret else {
+            return;
+        };
+        assert_ne!(stdout(&out), "0");
+        assert_eq!(fs::read_to_string(dir.join("ws_file")).unwrap(), "ws\n");
+        assert!(!PathBuf::from("/tmp/cw_private").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn limits() {
+        let sandbox = Sandbox::default();
+        if let Some((dir, out)) = run(&sandbox, true, "ulimit -t; grep -c : /proc/net/dev") {
+            assert_eq!(stdout(&out), "12\n1");
+            fs::remove_dir_all(dir).unwrap();
+        }
+        if let Some((dir, out)) = run(&sandbox, false, "ulimit -t") {
+            assert_eq!(stdout(&out), "unlimited");
+            fs::remove_dir_all(dir).unwrap();
+        }
+        let sandbox = Sandbox {
+            wall_time: Some(1),
+            ..Sandbox::default()
+        };
+        if let Some((dir, out)) = run(&sandbox, true, "sleep 5") {
+            assert_eq!(out.status, ExitStatus::TimedOut);
+            fs::remove_dir_all(dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn syscalls() {
+        if let Some((dir, out)) = run(&Sandbox::default(), false, "unshare -U true; echo $?") {
+            assert_ne!(stdout(&out), "0");
+            fs::remove_dir_all(dir).unwrap();
+        }
+    }
+}
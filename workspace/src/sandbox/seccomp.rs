@@ -0,0 +1,177 @@
+//! Seccomp filter denying syscalls useful to escape sandbox or inspect other processes
+
+use std::io;
+
+#[cfg(target_arch = "x86_64")]
+const AUDIT_ARCH: u32 = 0xc000_003e;
+#[cfg(target_arch = "aarch64")]
+const AUDIT_ARCH: u32 = 0xc000_00b7;
+
+/// syscalls failing with `EPERM` inside sandbox
+const DENIED_SYSCALLS: &[libc::c_long] = &[
+    libc::SYS_ptrace,
+    libc::SYS_process_vm_readv,
+    libc::SYS_process_vm_writev,
+    libc::SYS_mount,
+    libc::SYS_umount2,
+    libc::SYS_mount_setattr,
+    libc::SYS_open_tree,
+    libc::SYS_move_mount,
+    libc::SYS_fsopen,
+    libc::SYS_fsconfig,
+    libc::SYS_fsmount,
+    libc::SYS_fspick,
+    libc::SYS_pivot_root,
+    libc::SYS_chroot,
+    libc::SYS_setns,
+    libc::SYS_unshare,
+    libc::SYS_reboot,
+    libc::SYS_kexec_load,
+    libc::SYS_init_module,
+    libc::SYS_finit_module,
+    libc::SYS_delete_module,
+    libc::SYS_swapon,
+    libc::SYS_swapoff,
+    libc::SYS_bpf,
+    libc::SYS_perf_event_open,
+    libc::SYS_userfaultfd,
+    libc::SYS_keyctl,
+    libc::SYS_add_key,
+    libc::SYS_request_key,
+];
+
+/// `clone` flags creating namespaces, denied like `unshare`
+const NAMESPACE_FLAGS: libc::c_int = libc::CLONE_NEWUSER
+    | libc::CLONE_NEWNS
+    | libc::CLONE_NEWNET
+    | libc::CLONE_NEWPID
+    | libc::CLONE_NEWIPC
+    | libc::CLONE_NEWUTS
+    | libc::CLONE_NEWCGROUP;
+
+/// syscall numbers of x32 ABI have this bit set
+#[cfg(target_arch = "x86_64")]
+const X32_SYSCALL_BIT: u32 = 0x4000_0000;
+
+// offsets in `struct seccomp_data`
+const NR: u32 = 0;
+const ARCH: u32 = 4;
+/// low half of first argument on little endian targets
+const ARG0: u32 = 16;
+
+const LOAD: u32 = libc::BPF_LD | libc::BPF_W | libc::BPF_ABS;
+const RET: u32 = libc::BPF_RET | libc::BPF_K;
+
+fn stmt(code: u32, k: u32) -> libc::sock_filter {
+    libc::sock_filter {
+        code: code as u16,
+        jt: 0,
+        jf: 0,
+        k,
+    }
+}
+
+fn jump(op: u32, k: u32, jt: u8, jf: u8) -> libc::sock_filter {
+    libc::sock_filter {
+        code: (libc::BPF_JMP | op | libc::BPF_K) as u16,
+        jt,
+        jf,
+        k,
+    }
+}
+
+fn errno(e: libc::c_int) -> libc::sock_filter {
+    stmt(RET, libc::SECCOMP_RET_ERRNO | e as u32)
+}
+
+/// seccomp program killing foreign architectures and denying escape syscalls
+pub(super) fn filter() -> io::Result<Vec<libc::sock_filter>> {
+    let mut filter = vec![
+        stmt(LOAD, ARCH),
+        jump(libc::BPF_JEQ, AUDIT_ARCH, 1, 0),
+        stmt(RET, libc::SECCOMP_RET_KILL_PROCESS),
+        stmt(LOAD, NR),
+    ];
+    #[cfg(target_arch = "x86_64")]
+    filter.extend([
+        jump(libc::BPF_JSET, X32_SYSCALL_BIT, 0, 1),
+        errno(libc::ENOSYS),
+    ]);
+    filter.extend([
+        // clone without namespace flags is allowed
+        jump(libc::BPF_JEQ, libc::SYS_clone as u32, 0, 4),
+        stmt(LOAD, ARG0),
+        jump(libc::BPF_JSET, NAMESPACE_FLAGS as u32, 0, 1),
+        errno(libc::EPERM),
+        stmt(RET, libc::SECCOMP_RET_ALLOW),
+        // flags of clone3 are behind pointer, libc falls back to clone on ENOSYS
+        jump(libc::BPF_JEQ, libc::SYS_clone3 as u32, 0, 1),
+        errno(libc::ENOSYS),
+    ]);
+    for nr in DENIED_SYSCALLS {
+        filter.push(jump(libc::BPF_JEQ, *nr as u32, 0, 1));
+        filter.push(errno(libc::EPERM));
+    }
+    filter.push(stmt(RET, libc::SECCOMP_RET_ALLOW));
+    Ok(filter)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{filter, AUDIT_ARCH, NAMESPACE_FLAGS};
+
+    /// evaluate the subset of classic BPF used by `filter`
+    fn eval(filter: &[libc::sock_filter], nr: u32, arch: u32, arg0: u32) -> u32 {
+        let mut acc = 0;
+        let mut pc = 0;
+        loop {
+            let ins = filter[pc];
+            pc += 1;
+            let code = ins.code as u32;
+            if code == super::LOAD {
+                acc = match ins.k {
+                    super::NR => nr,
+                    super::ARCH => arch,
+                    super::ARG0 => arg0,
+                    k => panic!("unexpected offset {k}"),
+                };
+            } else if code == super::RET {
+                return ins.k;
+            } else {
+                let taken = match code & !libc::BPF_JMP {
+                    libc::BPF_JEQ => acc == ins.k,
+                    libc::BPF_JSET => acc & ins.k != 0,
+                    op => panic!("unexpected jump {op:#x}"),
+                };
+                pc += if taken { ins.jt } else { ins.jf } as usize;
+            }
+        }
+    }
+
+    #[test]
+    fn decisions() {
+        let filter = filter().unwrap();
+        let run =
+            |nr: libc::c_long, arg0: libc::c_int| eval(&filter, nr as u32, AUDIT_ARCH, arg0 as u32);
+        let allow = libc::SECCOMP_RET_ALLOW;
+        let eperm = libc::SECCOMP_RET_ERRNO | libc::EPERM as u32;
+        let enosys = libc::SECCOMP_RET_ERRNO | libc::ENOSYS as u32;
+
+        assert_eq!(run(libc::SYS_read, 0), allow);
+        assert_eq!(run(libc::SYS_unshare, libc::CLONE_NEWUSER), eperm);
+        assert_eq!(run(libc::SYS_ptrace, 0), eperm);
+        assert_eq!(
+            run(libc::SYS_clone, libc::CLONE_VM | libc::CLONE_THREAD),
+            allow
+        );
+        assert_eq!(run(libc::SYS_clone, libc::CLONE_NEWUSER), eperm);
+        assert_eq!(run(libc::SYS_clone, NAMESPACE_FLAGS), eperm);
+        assert_eq!(run(libc::SYS_clone3, 0), enosys);
+        assert_eq!(
+            eval(&filter, libc::SYS_read as u32, 0x4000_0003, 0),
+            libc::SECCOMP_RET_KILL_PROCESS
+        );
+        #[cfg(target_arch = "x86_64")]
+        assert_eq!(run(libc::SYS_unshare | 0x4000_0000, 0), enosys);
+    }
+}
@@ -1,17 +1,19 @@
 use std::{ffi::CStr, path::Path};
 
+use codewars_types::{catalog::Toolchain, KnownLangId};
 use rustix::{
     fd::{AsFd, BorrowedFd, OwnedFd},
     io::Errno,
 };
 
 use crate::{
-    util::{call_command_at, fs},
-    Config, WorkspaceObject,
+    util::{build_timeout, call_command_at, fs, toolchain},
+    Config, SubstitutedToolchain, WorkspaceObject,
 };
 
 const CODE_PATH: &CStr = c"src/solution.scala";
 const TEST_PATH: &CStr = c"sample/src/test.scala";
+const PRELOADED_PATH: &CStr = c"src/preloaded.scala";
 
 #[derive(Debug, thiserror::Error)]
 #[error(transparent)]
@@ -31,8 +33,6 @@ enum ScalafmtError {
 
 #[derive(Debug, thiserror::Error)]
 enum CreateErrorInner {
-    #[error("unsupported scala version")]
-    UnsupportedVersion,
     #[error("io error")]
     Io(#[source] Errno),
     #[error("failed to get scalafmt version")]
@@ -42,11 +42,6 @@ enum CreateErrorInner {
 #[error(transparent)]
 pub struct CreateError(#[from] CreateErrorInner);
 
-enum Version {
-    Scala2_13,
-    Scala3_0,
-}
-
 fn scalafmt_version() -> Result<String, ScalafmtError> {
     let output = std::process::Command::new("scalafmt")
         .arg("--version")
@@ -75,26 +70,23 @@ impl Scala {
     pub fn create(
         root: &Path,
         project: Config,
-        local_scala_version: &str,
-    ) -> Result<Self, CreateError> {
-        let version = match project.version_id {
-            "2.13" => Version::Scala2_13,
-            "3.0" => Version::Scala3_0,
-            _ => return Err(CreateError(CreateErrorInner::UnsupportedVersion)),
-        };
+    ) -> Result<(Self, Option<SubstitutedToolchain>), CreateError> {
+        let (toolchain, substituted) = toolchain(KnownLangId::Scala, project.version_id);
         let scalafmt = scalafmt_version().map_err(CreateErrorInner::ScalaFmtVersion)?;
 
         let root = fs::open_dirfd(root).map_err(CreateErrorInner::Io)?;
 
         fn inner(
             root: BorrowedFd,
-            version: Version,
+            toolchain: &Toolchain,
             project: Config,
-            local_scala_version: &str,
             scalafmt: &str,
         ) -> Result<(), Errno> {
             fs::mkdirat(root, c"src")?;
             fs::write(root, CODE_PATH, project.code)?;
+            if project.has_preload {
+                fs::write(root, PRELOADED_PATH, "")?;
+            }
 
             fs::mkdirat(root, c"sample")?;
             fs::mkdirat(root, c"sample/src")?;
@@ -106,9 +98,10 @@ impl Scala {
                 format!(
                     include_str!("./scala/scalafmt.conf"),
                     scalafmt_version = scalafmt,
-                    scala_version = match version {
-                        Version::Scala2_13 => "scala213",
-                        Version::Scala3_0 => "scala3",
+                    scala_version = if toolchain.version.starts_with("2.13") {
+                        "scala213"
+                    } else {
+                        "scala3"
                     }
                 ),
             )?;
@@ -118,26 +111,16 @@ impl Scala {
                 c"build.sc",
                 format!(
                     include_str!("./scala/build.sc"),
-                    scala_version = local_scala_version,
-                    test_framework = match version {
-                        Version::Scala2_13 => "org.scalatest::scalatest:3.0.8",
-                        Version::Scala3_0 => "org.scalatest::scalatest:3.2.10",
-                    }
+                    scala_version = toolchain.version,
+                    test_framework = toolchain.test_framework
                 ),
             )?;
 
             Ok(())
         }
-        inner(
-            root.as_fd(),
-            version,
-            project,
-            local_scala_version,
-            &scalafmt,
-        )
-        .map_err(CreateErrorInner::Io)?;
+        inner(root.as_fd(), toolchain, project, &scalafmt).map_err(CreateErrorInner::Io)?;
 
-        Ok(Scala { root })
+        Ok((Scala { root }, substituted))
     }
 }
 impl WorkspaceObject for Scala {
@@ -148,7 +131,7 @@ impl WorkspaceObject for Scala {
         })
     }
     fn clean_build(&self) -> Result<(), std::io::Error> {
-        call_command_at(self.root.as_fd(), "mill", ["clean"])?;
+        call_command_at(self.root.as_fd(), "mill", ["clean"], build_timeout())?.check()?;
         Ok(())
     }
     fn clean_session(&self) -> Result<(), std::io::Error> {
@@ -0,0 +1,100 @@
+use std::{ffi::CStr, path::Path};
+
+use rustix::{
+    fd::{AsFd, OwnedFd},
+    io::Errno,
+};
+
+use crate::{
+    util::{fs, test_timeout, Runner},
+    Code, Config, WorkspaceObject,
+};
+
+const PRELOADED_PATH: &CStr = c"preloaded.rb";
+const CODE_PATH: &CStr = c"solution.sh";
+const TEST_PATH: &CStr = c"spec/sample_spec.rb";
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct OpenError(Errno);
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct CreateError(#[from] Errno);
+
+pub struct Shell {
+    root: OwnedFd,
+}
+impl Shell {
+    pub fn open(root: &Path) -> Result<Self, OpenError> {
+        Ok(Self {
+            root: fs::open_dirfd(root).map_err(OpenError)?,
+        })
+    }
+    pub fn create(root: &Path, project: Config) -> Result<Self, CreateError> {
+        let root = fs::open_dirfd(root)?;
+
+        fs::write(root.as_fd(), c"Gemfile", include_str!("./shell/Gemfile"))?;
+        fs::write(root.as_fd(), c".rspec", include_str!("./ruby/rspec"))?;
+
+        fs::write(root.as_fd(), CODE_PATH, project.code)?;
+        if project.has_preload {
+            fs::write(root.as_fd(), PRELOADED_PATH, "")?;
+        }
+
+        fs::mkdirat(root.as_fd(), c"spec")?;
+        fs::write(root.as_fd(), TEST_PATH, project.fixture)?;
+        fs::write(
+            root.as_fd(),
+            c"spec/spec_helper.rb",
+            format!(
+                include_str!("./shell/spec_helper.rb.in"),
+                preloaded = if project.has_preload {
+                    "require_relative \"../preloaded\"\n"
+                } else {
+                    ""
+                }
+            ),
+        )?;
+
+        fs::mkdirat(root.as_fd(), c"spec/support")?;
+        fs::write(
+            root.as_fd(),
+            c"spec/support/codewars_shell.rb",
+            include_str!("./shell/codewars_shell.rb"),
+        )?;
+        fs::write(
+            root.as_fd(),
+            c"spec/support/codewars_test.rb",
+            include_str!("./ruby/codewars_test.rb"),
+        )?;
+
+        Ok(Self { root })
+    }
+}
+impl WorkspaceObject for Shell {
+    fn get_code(&self) -> Result<Code, std::io::Error> {
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), CODE_PATH)?,
+            fixture: fs::read_to_string(self.root.as_fd(), TEST_PATH)?,
+        })
+    }
+    fn clean_build(&self) -> Result<(), std::io::Error> {
+        Ok(())
+    }
+    fn clean_session(&self) -> Result<(), std::io::Error> {
+        fs::remove_dir_all_at(self.root.as_fd(), c".bundle")?;
+        fs::remove_at(self.root.as_fd(), c"Gemfile.lock")?;
+        Ok(())
+    }
+    /// Run fixture with rspec, solution is run by `run_shell` of the harness
+    fn local_test(&self) -> Result<bool, std::io::Error> {
+        Runner::new(self.root.as_fd(), "bundle")
+            .args(["exec", "rspec"])
+            .test(true)
+            .timeout(test_timeout())
+            .run()?
+            .check()?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,14 @@
+# Local replacement for the `run_shell` helper of the Codewars shell runner.
+require "open3"
+
+WORKSPACE_ROOT = File.expand_path("../..", __dir__)
+SOLUTION = File.join(WORKSPACE_ROOT, "solution.sh")
+
+# Run the solution with `args`, or an arbitrary `command`, and return its stdout.
+def run_shell(command: nil, args: [], output: true)
+  cmd = command ? ["bash", "-c", command] : ["bash", SOLUTION, *args.map(&:to_s)]
+  stdout, status = Open3.capture2(*cmd, chdir: WORKSPACE_ROOT)
+  puts stdout if output
+  warn "#{cmd.join(" ")} exited with #{status.exitstatus}" unless status.success?
+  stdout
+end
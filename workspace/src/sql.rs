@@ -0,0 +1,106 @@
+use std::{ffi::CStr, path::Path};
+
+use rustix::{
+    fd::{AsFd, OwnedFd},
+    io::Errno,
+};
+
+use crate::{
+    util::{fs, test_timeout, Runner},
+    Code, Config, WorkspaceObject,
+};
+
+const PRELOADED_PATH: &CStr = c"preloaded.rb";
+const CODE_PATH: &CStr = c"solution.sql";
+const TEST_PATH: &CStr = c"spec/sample_spec.rb";
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct OpenError(Errno);
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct CreateError(#[from] Errno);
+
+pub struct Sql {
+    root: OwnedFd,
+}
+impl Sql {
+    pub fn open(root: &Path) -> Result<Self, OpenError> {
+        Ok(Self {
+            root: fs::open_dirfd(root).map_err(OpenError)?,
+        })
+    }
+    pub fn create(root: &Path, project: Config) -> Result<Self, CreateError> {
+        let root = fs::open_dirfd(root)?;
+
+        fs::write(root.as_fd(), c"Gemfile", include_str!("./sql/Gemfile"))?;
+        fs::write(root.as_fd(), c".rspec", include_str!("./ruby/rspec"))?;
+
+        fs::write(root.as_fd(), CODE_PATH, project.code)?;
+        if project.has_preload {
+            fs::write(root.as_fd(), PRELOADED_PATH, "")?;
+        }
+
+        fs::mkdirat(root.as_fd(), c"spec")?;
+        fs::write(root.as_fd(), TEST_PATH, project.fixture)?;
+        fs::write(
+            root.as_fd(),
+            c"spec/spec_helper.rb",
+            format!(
+                include_str!("./sql/spec_helper.rb.in"),
+                preloaded = if project.has_preload {
+                    "require_relative \"../preloaded\"\n"
+                } else {
+                    ""
+                }
+            ),
+        )?;
+
+        fs::mkdirat(root.as_fd(), c"spec/support")?;
+        fs::write(
+            root.as_fd(),
+            c"spec/support/codewars_sql.rb",
+            include_str!("./sql/codewars_sql.rb"),
+        )?;
+        fs::write(
+            root.as_fd(),
+            c"spec/support/sql_statements.rb",
+            include_str!("./sql/sql_statements.rb"),
+        )?;
+        // legacy fixtures use the `Test` api of the ruby runner
+        fs::write(
+            root.as_fd(),
+            c"spec/support/codewars_test.rb",
+            include_str!("./ruby/codewars_test.rb"),
+        )?;
+
+        Ok(Self { root })
+    }
+}
+impl WorkspaceObject for Sql {
+    fn get_code(&self) -> Result<Code, std::io::Error> {
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), CODE_PATH)?,
+            fixture: fs::read_to_string(self.root.as_fd(), TEST_PATH)?,
+        })
+    }
+    fn clean_build(&self) -> Result<(), std::io::Error> {
+        Ok(())
+    }
+    fn clean_session(&self) -> Result<(), std::io::Error> {
+        fs::remove_dir_all_at(self.root.as_fd(), c".bundle")?;
+        fs::remove_at(self.root.as_fd(), c"Gemfile.lock")?;
+        Ok(())
+    }
+    /// Run fixture with rspec against in-memory SQLite, or `DATABASE_URL` if set
+    fn local_test(&self) -> Result<bool, std::io::Error> {
+        Runner::new(self.root.as_fd(), "bundle")
+            .args(["exec", "rspec"])
+            .test(true)
+            .timeout(test_timeout())
+            .run()?
+            .check()?;
+        Ok(true)
+    }
+}
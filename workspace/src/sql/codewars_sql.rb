@@ -0,0 +1,30 @@
+# Local replacement for the Codewars SQL test environment.
+#
+# Fixtures get a Sequel connection `DB`, the solution in `$sql` and the
+# `run_sql`/`compare_with` helpers. Set `DATABASE_URL` (for example
+# `postgres://localhost/codewars`) to run against PostgreSQL like Codewars does,
+# otherwise an in-memory SQLite database is used.
+require "sequel"
+require_relative "sql_statements"
+
+DB = ENV["DATABASE_URL"] ? Sequel.connect(ENV["DATABASE_URL"]) : Sequel.sqlite
+$sql = File.read(File.expand_path("../../solution.sql", __dir__))
+
+# Run every statement of `sql` and return the rows of the last one.
+def run_sql(sql = $sql)
+  *setup, query = sql_statements(sql)
+  setup.each { |s| DB.run(s) }
+  query.nil? ? [] : DB[query].to_a
+end
+
+# Compare rows returned by the solution with rows of the expected query.
+def compare_with(expected, limit: 100, sort: nil)
+  actual = run_sql
+  expected = expected.is_a?(String) ? DB[expected].to_a : expected
+  if sort
+    actual = actual.sort_by { |r| r[sort] }
+    expected = expected.sort_by { |r| r[sort] }
+  end
+  expect(actual.take(limit)).to eq(expected.take(limit))
+  actual
+end
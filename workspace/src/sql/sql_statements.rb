@@ -0,0 +1,33 @@
+require "strscan"
+
+# Split SQL script into statements at `;` outside of string literals, quoted
+# identifiers, comments and dollar quoted function bodies. Statements without
+# code are dropped.
+def sql_statements(sql)
+  statements = []
+  current = +""
+  code = false
+  scanner = StringScanner.new(sql)
+  until scanner.eos?
+    if scanner.scan(/--[^\n]*/) || scanner.scan(%r{/\*.*?(?:\*/|\z)}m)
+      current << scanner.matched
+    elsif scanner.scan(/'(?:[^']|'')*'?/) || scanner.scan(/"(?:[^"]|"")*"?/)
+      current << scanner.matched
+      code = true
+    elsif (tag = scanner.scan(/\$(?:[A-Za-z_]\w*)?\$/))
+      body = scanner.scan_until(/#{Regexp.escape(tag)}/) || scanner.rest.tap { scanner.terminate }
+      current << tag << body
+      code = true
+    elsif scanner.skip(/;/)
+      statements << current.strip if code
+      current = +""
+      code = false
+    else
+      char = scanner.getch
+      current << char
+      code ||= !char.match?(/\s/)
+    end
+  end
+  statements << current.strip if code
+  statements
+end
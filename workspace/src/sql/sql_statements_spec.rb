@@ -0,0 +1,29 @@
+# Spec of the statement splitter used by the SQL harness, run with
+# `rspec workspace/src/sql/sql_statements_spec.rb`.
+require_relative "sql_statements"
+
+RSpec.describe "sql_statements" do
+  it "splits at semicolons" do
+    expect(sql_statements("CREATE TABLE t (a int);\nSELECT * FROM t;\n"))
+      .to eq(["CREATE TABLE t (a int)", "SELECT * FROM t"])
+  end
+
+  it "keeps semicolons in quotes" do
+    sql = "INSERT INTO t VALUES ('a;\nb', 'it''s;\n');\nSELECT \"x;\ny\" FROM t"
+    expect(sql_statements(sql))
+      .to eq(["INSERT INTO t VALUES ('a;\nb', 'it''s;\n')", "SELECT \"x;\ny\" FROM t"])
+  end
+
+  it "keeps dollar quoted function bodies" do
+    sql = "CREATE FUNCTION f() RETURNS int AS $body$\nBEGIN\n  RETURN 1;\nEND;\n$body$ LANGUAGE plpgsql;\nSELECT f()"
+    expect(sql_statements(sql)).to eq([
+      "CREATE FUNCTION f() RETURNS int AS $body$\nBEGIN\n  RETURN 1;\nEND;\n$body$ LANGUAGE plpgsql",
+      "SELECT f()"
+    ])
+  end
+
+  it "drops statements with only comments" do
+    expect(sql_statements("-- a;\nSELECT 1; /* b; */\n-- trailing"))
+      .to eq(["-- a;\nSELECT 1"])
+  end
+end
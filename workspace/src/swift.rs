@@ -0,0 +1,104 @@
+use std::{ffi::CStr, path::Path};
+
+use codewars_types::{catalog::Toolchain, KnownLangId};
+use rustix::{
+    fd::{AsFd, BorrowedFd, OwnedFd},
+    io::Errno,
+};
+
+use crate::{
+    util::{build_timeout, call_command_at, fs, toolchain},
+    Code, Config, SubstitutedToolchain, WorkspaceObject,
+};
+
+const PRELOADED_PATH: &CStr = c"Sources/Solution/preloaded.swift";
+const CODE_PATH: &CStr = c"Sources/Solution/solution.swift";
+const TEST_PATH: &CStr = c"Tests/SolutionTests/SolutionTests.swift";
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct OpenError(#[from] Errno);
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct CreateError(#[from] Errno);
+
+/// swift tools version used in package manifest, like `5.9` for toolchain `5.9.2`
+fn tools_version(toolchain: &Toolchain) -> &str {
+    let v = toolchain.version;
+    match v.match_indices('.').nth(1) {
+        Some((idx, _)) => &v[..idx],
+        None => v,
+    }
+}
+
+pub struct Swift {
+    root: OwnedFd,
+}
+impl Swift {
+    pub fn open(root: &Path) -> Result<Self, OpenError> {
+        Ok(Self {
+            root: fs::open_dirfd(root)?,
+        })
+    }
+    pub fn create(
+        root: &Path,
+        project: Config,
+    ) -> Result<(Self, Option<SubstitutedToolchain>), CreateError> {
+        let (toolchain, substituted) = toolchain(KnownLangId::Swift, project.version_id);
+        let tools_version = tools_version(toolchain);
+        let root = fs::open_dirfd(root)?;
+
+        fn inner(root: BorrowedFd, project: Config, tools_version: &str) -> Result<(), Errno> {
+            fs::write(
+                root,
+                c"Package.swift",
+                format!(
+                    include_str!("./swift/Package.swift"),
+                    tools_version = tools_version,
+                    package = project.slug
+                ),
+            )?;
+
+            fs::mkdirat(root, c"Sources")?;
+            fs::mkdirat(root, c"Sources/Solution")?;
+            fs::write(root, CODE_PATH, project.code)?;
+            if project.has_preload {
+                fs::write(root, PRELOADED_PATH, "")?;
+            }
+
+            fs::mkdirat(root, c"Tests")?;
+            fs::mkdirat(root, c"Tests/SolutionTests")?;
+            fs::write(root, TEST_PATH, project.fixture)?;
+
+            Ok(())
+        }
+        inner(root.as_fd(), project, tools_version)?;
+
+        Ok((Self { root }, substituted))
+    }
+}
+impl WorkspaceObject for Swift {
+    fn get_code(&self) -> Result<Code, std::io::Error> {
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), CODE_PATH)?,
+            fixture: fs::read_to_string(self.root.as_fd(), TEST_PATH)?,
+        })
+    }
+    fn clean_build(&self) -> Result<(), std::io::Error> {
+        call_command_at(
+            self.root.as_fd(),
+            "swift",
+            ["package", "clean"],
+            build_timeout(),
+        )?
+        .check()?;
+        Ok(())
+    }
+    fn clean_session(&self) -> Result<(), std::io::Error> {
+        fs::remove_dir_all_at(self.root.as_fd(), c".build")?;
+        fs::remove_dir_all_at(self.root.as_fd(), c".swiftpm")?;
+        fs::remove_at(self.root.as_fd(), c"Package.resolved")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,14 @@
+// swift-tools-version:{tools_version}
+import PackageDescription
+
+let package = Package(
+    name: "{package}",
+    targets: [
+        .target(name: "Solution", path: "Sources/Solution"),
+        .testTarget(
+            name: "SolutionTests",
+            dependencies: ["Solution"],
+            path: "Tests/SolutionTests"
+        ),
+    ]
+)
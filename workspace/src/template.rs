@@ -0,0 +1,470 @@
+//! Workspaces described by declarative templates instead of Rust code.
+//!
+//! A template is a json file `<language id>.json` in a template directory, it takes precedence
+//! over builtin workspace of the language and may also be given for languages without one, e.g.
+//!
+//! ```json
+//! {
+//!   "files": [
+//!     { "path": "Cargo.toml", "include": "rust/Cargo.toml" },
+//!     { "path": "rustfmt.toml", "content": "edition = \"2021\"\n" }
+//!   ],
+//!   "solution": "src/lib.rs",
+//!   "fixture": "tests/sample.rs",
+//!   "fixture_header": "use {crate_name}::*;\n",
+//!   "clean_build": [["cargo", "clean"]],
+//!   "test": ["cargo", "test"],
+//!   "test_timeout": 60
+//! }
+//! ```
+//!
+//! Paths, file contents and commands may contain placeholders `{name}`, `{{` and `}}` are
+//! literal braces. `{name:path}` replaces `.` in the value with `/`. Builtin placeholders are
+//! `slug`, `package`, `crate_name`, `version` and `full_version`, more can be extracted from
+//! code with `names` rules.
+
+use std::{
+    collections::BTreeMap,
+    ffi::CStr,
+    fs as std_fs, io,
+    path::{Component, Path, PathBuf},
+    time::Duration,
+};
+
+use rustix::{
+    fd::{AsFd, OwnedFd},
+    io::Errno,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    util::{
+        build_timeout, call_command_at, fs, full_version, package_name, process, slug_name, Runner,
+    },
+    Code, Config, WorkspaceObject,
+};
+
+const STATE_FILE: &CStr = c"template_state.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Source {
+    /// inline file content
+    Content(String),
+    /// file relative to template directory
+    Include(PathBuf),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct File {
+    pub path: String,
+    #[serde(flatten)]
+    pub source: Source,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NameSource {
+    Solution,
+    Fixture,
+}
+
+/// Take the word following `prefix` at the start of the first matching line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NameRule {
+    pub from: NameSource,
+    pub prefix: String,
+    #[serde(default)]
+    pub default: Option<String>,
+}
+impl NameRule {
+    fn extract<'a>(&self, src: &'a str) -> Option<&'a str> {
+        src.lines().find_map(|l| {
+            let s = l.trim_start().strip_prefix(self.prefix.as_str())?;
+            let s = if self.prefix.ends_with(char::is_alphanumeric) {
+                s.strip_prefix(char::is_whitespace)?.trim_start()
+            } else {
+                s.trim_start()
+            };
+            let end = s
+                .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '.' | '\'' | '-')))
+                .unwrap_or(s.len());
+            (end != 0).then(|| &s[..end])
+        })
+    }
+}
+
+pub type Command = Vec<String>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Template {
+    #[serde(default)]
+    pub files: Vec<File>,
+    /// path of solution code
+    pub solution: String,
+    /// path of sample tests
+    pub fixture: String,
+    /// path of preloaded code, created empty when kata has preloaded code
+    #[serde(default)]
+    pub preloaded: Option<String>,
+    /// prepended to fixture and removed when reading code back
+    #[serde(default)]
+    pub fixture_header: Option<String>,
+    #[serde(default)]
+    pub names: BTreeMap<String, NameRule>,
+    #[serde(default)]
+    pub clean_build: Vec<Command>,
+    #[serde(default)]
+    pub clean_session: Vec<Command>,
+    #[serde(default)]
+    pub test: Option<Command>,
+    /// wall clock timeout of test command in seconds, overrides configured test timeout
+    #[serde(default)]
+    pub test_timeout: Option<u64>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum LoadError {
+    #[error("failed to read {1}")]
+    Io(#[source] io::Error, PathBuf),
+    #[error("invalid template {1}")]
+    Json(#[source] serde_json::Error, PathBuf),
+}
+
+impl Template {
+    /// Load template of `lang` from `dir`, `None` if there is no such template
+    pub fn load(dir: &Path, lang: &str) -> Result<Option<Self>, LoadError> {
+        let path = dir.join(format!("{lang}.json"));
+        let data = match std_fs::read(&path) {
+            Ok(d) => d,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(LoadError::Io(e, path)),
+        };
+        let mut ret: Self = serde_json::from_slice(&data).map_err(|e| LoadError::Json(e, path))?;
+        // inline included files so workspace does not depend on template directory
+        for f in ret.files.iter_mut() {
+            if let Source::Include(p) = &f.source {
+                let p = dir.join(p);
+                f.source =
+                    Source::Content(std_fs::read_to_string(&p).map_err(|e| LoadError::Io(e, p))?);
+            }
+        }
+        Ok(Some(ret))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ExpandError {
+    #[error("unknown placeholder {0}")]
+    Unknown(String),
+    #[error("unknown placeholder format {0}")]
+    Format(String),
+    #[error("unclosed placeholder")]
+    Unclosed,
+    #[error("unmatched `}}`")]
+    Unmatched,
+}
+
+/// Replace placeholders in `s` with values of `vars`
+fn expand(s: &str, vars: &BTreeMap<String, String>) -> Result<String, ExpandError> {
+    let mut ret = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(idx) = rest.find(['{', '}']) {
+        ret.push_str(&rest[..idx]);
+        let t = &rest[idx..];
+        if let Some(t) = t.strip_prefix("{{") {
+            ret.push('{');
+            rest = t;
+        } else if let Some(t) = t.strip_prefix("}}") {
+            ret.push('}');
+            rest = t;
+        } else if t.starts_with('}') {
+            return Err(ExpandError::Unmatched);
+        } else {
+            let (key, t) = t[1..].split_once('}').ok_or(ExpandError::Unclosed)?;
+            let (name, format) = match key.split_once(':') {
+                Some((n, f)) => (n, Some(f)),
+                None => (key, None),
+            };
+            let value = vars
+                .get(name)
+                .ok_or_else(|| ExpandError::Unknown(name.to_string()))?;
+            match format {
+                None => ret.push_str(value),
+                Some("path") => ret.push_str(&value.replace('.', "/")),
+                Some(f) => return Err(ExpandError::Format(f.to_string())),
+            }
+            rest = t;
+        }
+    }
+    ret.push_str(rest);
+    Ok(ret)
+}
+
+/// Directory containing file `path`, `None` unless `path` names a file inside workspace
+fn file_parent(path: &str) -> Option<&Path> {
+    let path = Path::new(path);
+    if path
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return None;
+    }
+    path.parent()
+}
+
+/// Template with placeholders expanded, saved in workspace
+#[derive(Serialize, Deserialize)]
+struct State {
+    solution: String,
+    fixture: String,
+    #[serde(default)]
+    fixture_header: Option<String>,
+    clean_build: Vec<Command>,
+    clean_session: Vec<Command>,
+    test: Option<Command>,
+    #[serde(default)]
+    test_timeout: Option<u64>,
+}
+
+#[derive(Debug, thiserror::Error)]
+enum OpenErrInner {
+    #[error("failed to open state")]
+    Io(#[source] Errno),
+    #[error("failed to deserialize json")]
+    Json(#[source] serde_json::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct OpenError(#[from] OpenErrInner);
+
+#[derive(Debug, thiserror::Error)]
+enum CreateErrorInner {
+    #[error("failed to find {0} in code")]
+    Name(String),
+    #[error("failed to expand {0}")]
+    Expand(String, #[source] ExpandError),
+    #[error("failed to write {0}")]
+    Write(String, #[source] io::Error),
+    #[error("included file {0} is not loaded")]
+    Include(PathBuf),
+    #[error("invalid file path {0:?}")]
+    Path(String),
+    #[error("io error")]
+    Io(#[source] Errno),
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct CreateError(#[from] CreateErrorInner);
+
+pub struct Custom {
+    root: OwnedFd,
+    state: State,
+}
+impl Custom {
+    pub fn open(root: &Path) -> Result<Self, OpenError> {
+        let root = fs::open_dirfd(root).map_err(OpenErrInner::Io)?;
+        Ok(Self {
+            state: serde_json::from_slice(
+                &fs::read(root.as_fd(), STATE_FILE).map_err(OpenErrInner::Io)?,
+            )
+            .map_err(OpenErrInner::Json)?,
+            root,
+        })
+    }
+    /// Whether workspace at `root` was created from a template
+    pub fn is_template(root: &Path) -> bool {
+        root.join(STATE_FILE.to_str().unwrap()).exists()
+    }
+    pub fn create(root: &Path, project: Config, template: Template) -> Result<Self, CreateError> {
+        let root = fs::open_dirfd(root).map_err(CreateErrorInner::Io)?;
+
+        let mut vars = BTreeMap::from([
+            ("slug".to_string(), project.slug.to_string()),
+            ("package".to_string(), slug_name(project.slug).into_owned()),
+            ("crate_name".to_string(), package_name(project.slug)),
+            ("version".to_string(), project.version_id.to_string()),
+            (
+                "full_version".to_string(),
+                full_version(project.version_id).into_owned(),
+            ),
+        ]);
+        for (name, rule) in template.names.iter() {
+            let src = match rule.from {
+                NameSource::Solution => project.code,
+                NameSource::Fixture => project.fixture,
+            };
+            let value = rule
+                .extract(src)
+                .or(rule.default.as_deref())
+                .ok_or_else(|| CreateErrorInner::Name(name.clone()))?;
+            vars.insert(name.clone(), value.to_string());
+        }
+        let expand =
+            |s: &str| expand(s, &vars).map_err(|e| CreateErrorInner::Expand(s.to_string(), e));
+        let expand_cmds = |cmds: &[Command]| -> Result<Vec<Command>, CreateErrorInner> {
+            cmds.iter()
+                .map(|c| c.iter().map(|a| expand(a)).collect())
+                .collect()
+        };
+        let write = |path: &str, content: &str| {
+            let path = expand(path)?;
+            let parent = file_parent(&path).ok_or_else(|| CreateErrorInner::Path(path.clone()))?;
+            fs::mkdir_all_at(root.as_fd(), parent)
+                .and_then(|_| Ok(fs::write(root.as_fd(), path.as_str(), content)?))
+                .map_err(|e| CreateErrorInner::Write(path.clone(), e))?;
+            Ok::<_, CreateErrorInner>(path)
+        };
+
+        for f in template.files.iter() {
+            let content = match &f.source {
+                Source::Content(c) => expand(c)?,
+                Source::Include(p) => return Err(CreateErrorInner::Include(p.clone()).into()),
+            };
+            write(&f.path, &content)?;
+        }
+
+        let fixture_header = template.fixture_header.as_deref().map(expand).transpose()?;
+        let state = State {
+            solution: write(&template.solution, project.code)?,
+            fixture: write(
+                &template.fixture,
+                &match &fixture_header {
+                    Some(h) => format!("{h}{}", project.fixture),
+                    None => project.fixture.to_string(),
+                },
+            )?,
+            fixture_header,
+            clean_build: expand_cmds(&template.clean_build)?,
+            clean_session: expand_cmds(&template.clean_session)?,
+            test: template
+                .test
+                .as_ref()
+                .map(|c| c.iter().map(|a| expand(a)).collect())
+                .transpose()?,
+            test_timeout: template.test_timeout,
+        };
+        if project.has_preload {
+            if let Some(p) = &template.preloaded {
+                write(p, "")?;
+            }
+        }
+        fs::write(
+            root.as_fd(),
+            STATE_FILE,
+            serde_json::to_vec(&state).unwrap(),
+        )
+        .map_err(CreateErrorInner::Io)?;
+
+        Ok(Self { root, state })
+    }
+    fn run(&self, cmds: &[Command]) -> io::Result<()> {
+        for c in cmds {
+            if let Some((program, args)) = c.split_first() {
+                call_command_at(self.root.as_fd(), program, args, build_timeout())?.check()?;
+            }
+        }
+        Ok(())
+    }
+}
+impl WorkspaceObject for Custom {
+    fn get_code(&self) -> Result<Code, io::Error> {
+        let fixture = fs::read_to_string(self.root.as_fd(), self.state.fixture.as_str())?;
+        Ok(Code {
+            solution: fs::read_to_string(self.root.as_fd(), self.state.solution.as_str())?,
+            fixture: match &self.state.fixture_header {
+                Some(h) => fixture.strip_prefix(h.as_str()).map(str::to_string),
+                None => None,
+            }
+            .unwrap_or(fixture),
+        })
+    }
+    fn clean_build(&self) -> Result<(), io::Error> {
+        self.run(&self.state.clean_build)
+    }
+    fn clean_session(&self) -> Result<(), io::Error> {
+        self.run(&self.state.clean_session)?;
+        fs::remove_at(self.root.as_fd(), STATE_FILE)?;
+        Ok(())
+    }
+    fn local_test(&self) -> Result<bool, io::Error> {
+        match self.state.test.as_ref().and_then(|c| c.split_first()) {
+            Some((program, args)) => {
+                Runner::new(self.root.as_fd(), program)
+                    .args(args)
+                    .test(true)
+                    .timeout(
+                        self.state
+                            .test_timeout
+                            .map(Duration::from_secs)
+                            .or(process::timeouts().test),
+                    )
+                    .run()?
+                    .check()?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use std::path::Path;
+
+    use super::{expand, file_parent, NameRule, NameSource};
+
+    #[test]
+    fn placeholder() {
+        let vars = BTreeMap::from([
+            ("crate_name".to_string(), "multiply".to_string()),
+            ("module".to_string(), "Codewars.Kata".to_string()),
+        ]);
+        assert_eq!(
+            expand("use {crate_name}::*;", &vars).unwrap(),
+            "use multiply::*;"
+        );
+        assert_eq!(
+            expand("src/{module:path}.hs {{x}}", &vars).unwrap(),
+            "src/Codewars/Kata.hs {x}"
+        );
+        assert!(expand("{unknown}", &vars).is_err());
+        assert!(expand("{module", &vars).is_err());
+        assert!(expand("}", &vars).is_err());
+    }
+
+    #[test]
+    fn name_rule() {
+        let rule = NameRule {
+            from: NameSource::Solution,
+            prefix: "module".to_string(),
+            default: None,
+        };
+        assert_eq!(
+            rule.extract("-- comment\nmodule Kata.Solution (f) where"),
+            Some("Kata.Solution")
+        );
+        assert_eq!(rule.extract("modules Foo"), None);
+
+        let rule = NameRule {
+            prefix: "-module(".to_string(),
+            ..rule
+        };
+        assert_eq!(rule.extract("-module(kata).\n"), Some("kata"));
+    }
+
+    #[test]
+    fn parent() {
+        assert_eq!(file_parent("src/lib.rs"), Some(Path::new("src")));
+        assert_eq!(file_parent("Cargo.toml"), Some(Path::new("")));
+        assert_eq!(file_parent(""), None);
+        assert_eq!(file_parent("/"), None);
+        assert_eq!(file_parent("/etc/passwd"), None);
+        assert_eq!(file_parent("../outside.txt"), None);
+        assert_eq!(file_parent("src/../../outside.txt"), None);
+    }
+}
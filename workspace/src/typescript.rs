@@ -1,14 +1,35 @@
 use std::{ffi::CStr, path::Path};
 
+use codewars_types::{catalog::Toolchain, KnownLangId};
 use rustix::{
     fd::{AsFd, OwnedFd},
     io::Errno,
 };
 
-use crate::{util::fs, Code, Config, WorkspaceObject};
+use crate::{
+    util::{build_timeout, call_command_at, fs, toolchain},
+    Code, Config, SubstitutedToolchain, WorkspaceObject,
+};
+
+// solution, preloaded and tests share directory so fixture can import
+// `./solution` and `./preloaded` like on Codewars
+const CODE_PATH: &CStr = c"src/solution.ts";
+const TEST_PATH: &CStr = c"src/test.ts";
+const PRELOADED_PATH: &CStr = c"src/preloaded.ts";
 
-const CODE_PATH: &CStr = c"src/index.ts";
-const TEST_PATH: &CStr = c"test/sample.ts";
+/// paths of workspaces created before solution and tests shared `src`
+const LEGACY_CODE_PATH: &CStr = c"src/index.ts";
+const LEGACY_TEST_PATH: &CStr = c"test/sample.ts";
+
+/// `devDependencies` entries of typescript and test packages
+fn dev_dependencies(toolchain: &Toolchain) -> String {
+    [("typescript", toolchain.version)]
+        .iter()
+        .chain(toolchain.test_packages)
+        .map(|(name, version)| format!("    \"{name}\": \"{version}\""))
+        .collect::<Vec<_>>()
+        .join(",\n")
+}
 
 #[derive(Debug, thiserror::Error)]
 #[error(transparent)]
@@ -27,13 +48,21 @@ impl TypeScript {
             root: fs::open_dirfd(path).map_err(OpenError)?,
         })
     }
-    pub fn create(root: &Path, config: Config) -> Result<Self, CreateError> {
+    pub fn create(
+        root: &Path,
+        config: Config,
+    ) -> Result<(Self, Option<SubstitutedToolchain>), CreateError> {
+        let (toolchain, substituted) = toolchain(KnownLangId::TypeScript, config.version_id);
         let root = fs::open_dirfd(root)?;
 
         fs::write(
             root.as_fd(),
             c"tsconfig.json",
-            include_str!("./typescript/tsconfig.json"),
+            format!(
+                include_str!("./typescript/tsconfig.json.in"),
+                target = toolchain.option("target").unwrap_or("ES2022"),
+                module = toolchain.option("module").unwrap_or("CommonJS")
+            ),
         )?;
         fs::write(
             root.as_fd(),
@@ -41,29 +70,42 @@ impl TypeScript {
             format!(
                 include_str!("./typescript/package.json.in"),
                 package = config.slug,
-                tsc_version = config.version_id
+                dev_dependencies = dev_dependencies(toolchain)
             ),
         )?;
 
         fs::mkdirat(root.as_fd(), c"src")?;
         fs::write(root.as_fd(), CODE_PATH, config.code)?;
-
-        fs::mkdirat(root.as_fd(), c"test")?;
-        rustix::fs::symlinkat(c"../src", root.as_fd(), c"test/solution")?;
         fs::write(root.as_fd(), TEST_PATH, config.fixture)?;
+        if config.has_preload {
+            fs::write(root.as_fd(), PRELOADED_PATH, "export {};\n")?;
+        }
 
-        Ok(Self { root })
+        Ok((Self { root }, substituted))
     }
 }
 impl WorkspaceObject for TypeScript {
     fn get_code(&self) -> Result<crate::Code, std::io::Error> {
-        Ok(Code {
-            solution: fs::read_to_string(self.root.as_fd(), CODE_PATH)?,
-            fixture: fs::read_to_string(self.root.as_fd(), TEST_PATH)?,
-        })
+        match fs::read_to_string(self.root.as_fd(), CODE_PATH) {
+            Ok(solution) => Ok(Code {
+                solution,
+                fixture: fs::read_to_string(self.root.as_fd(), TEST_PATH)?,
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Code {
+                solution: fs::read_to_string(self.root.as_fd(), LEGACY_CODE_PATH)?,
+                fixture: fs::read_to_string(self.root.as_fd(), LEGACY_TEST_PATH)?,
+            }),
+            Err(e) => Err(e),
+        }
     }
     fn clean_build(&self) -> Result<(), std::io::Error> {
-        fs::remove_dir_all_at(self.root.as_fd(), c"dist")?;
+        call_command_at(
+            self.root.as_fd(),
+            "pnpm",
+            ["exec", "tsc", "--build", "--clean"],
+            build_timeout(),
+        )?
+        .check()?;
         Ok(())
     }
     fn clean_session(&self) -> Result<(), std::io::Error> {
@@ -72,3 +114,19 @@ impl WorkspaceObject for TypeScript {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use codewars_types::{catalog, KnownLangId};
+
+    use super::dev_dependencies;
+
+    #[test]
+    fn package_json() {
+        let toolchain = catalog::toolchain(KnownLangId::TypeScript, "4.9").unwrap();
+        assert_eq!(
+            dev_dependencies(toolchain),
+            "    \"typescript\": \"4.9.5\",\n    \"mocha\": \"10.2.0\",\n    \"@types/mocha\": \"10.0.1\",\n    \"chai\": \"4.3.7\",\n    \"@types/chai\": \"4.3.4\""
+        );
+    }
+}
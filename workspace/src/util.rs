@@ -1,28 +1,137 @@
-use std::{ffi::OsStr, io, os::unix::process::CommandExt, process};
+use std::{
+    borrow::Cow,
+    ffi::{CString, OsStr},
+    io,
+    path::Path,
+    time::Duration,
+};
 
-use rustix::fd::{AsRawFd, BorrowedFd};
+use codewars_types::{
+    catalog::{self, Toolchain},
+    KnownLangId,
+};
+use rustix::fd::BorrowedFd;
+
+use crate::SubstitutedToolchain;
 
 pub mod fs;
+pub mod process;
+pub use process::{Output, Runner};
+
+/// package name derived from kata slug, prefixed when slug does not start with a letter
+pub fn slug_name(slug: &str) -> Cow<'_, str> {
+    if slug.starts_with(|c: char| !c.is_alphabetic()) {
+        Cow::Owned(format!("cw-{}", slug))
+    } else {
+        Cow::Borrowed(slug)
+    }
+}
 
-pub fn call_command_at<S, I, SA>(pwd: BorrowedFd, program: S, args: I) -> io::Result<()>
+/// identifier usable as package or application name derived from kata slug
+pub fn package_name(slug: &str) -> String {
+    slug_name(slug).replace('-', "_")
+}
+
+/// pad version like `1.10` to `1.10.0`
+pub fn full_version(version: &str) -> Cow<'_, str> {
+    match version.matches('.').count() {
+        0 => Cow::Owned(format!("{version}.0.0")),
+        1 => Cow::Owned(format!("{version}.0")),
+        _ => Cow::Borrowed(version),
+    }
+}
+
+/// Catalog toolchain of `version_id`, or the newest toolchain of `lang` with the substitution
+/// if the version is unknown
+///
+/// Panics if the catalog has no toolchain of `lang`, only call for languages listed there.
+pub fn toolchain(
+    lang: KnownLangId,
+    version_id: &str,
+) -> (&'static Toolchain, Option<SubstitutedToolchain>) {
+    match catalog::toolchain(lang, version_id) {
+        Some(t) => (t, None),
+        None => {
+            let toolchain = catalog::latest(lang).expect("language missing from catalog");
+            (
+                toolchain,
+                Some(SubstitutedToolchain {
+                    version_id: version_id.to_string(),
+                    toolchain,
+                }),
+            )
+        }
+    }
+}
+
+/// write module source to `base/M1/M2.ext` for dot separated module name `M1.M2`
+pub fn write_module(
+    root: BorrowedFd,
+    base: &str,
+    module: &str,
+    ext: &str,
+    code: &str,
+) -> io::Result<CString> {
+    let mut rel_path = String::from(base);
+    for m in module.split('.') {
+        if !rel_path.is_empty() {
+            rel_path.push('/');
+        }
+        rel_path.push_str(m);
+    }
+    rel_path.push('.');
+    rel_path.push_str(ext);
+
+    fs::mkdir_all_at(root, Path::new(rel_path.as_str()).parent().unwrap())?;
+    let path =
+        CString::new(rel_path).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    fs::write(root, &path, code)?;
+    Ok(path)
+}
+
+/// Run program in `pwd` with wall clock `timeout`, returning its status and output
+///
+/// Exit status is not checked, see [`Output::check`].
+pub fn call_command_at<S, I, SA>(
+    pwd: BorrowedFd,
+    program: S,
+    args: I,
+    timeout: Option<Duration>,
+) -> io::Result<Output>
 where
     S: AsRef<OsStr>,
     SA: AsRef<OsStr>,
     I: IntoIterator<Item = SA>,
 {
-    let pwd = pwd.as_raw_fd();
-    let s = unsafe {
-        process::Command::new(program)
-            .args(args)
-            .pre_exec(move || {
-                rustix::process::fchdir(BorrowedFd::borrow_raw(pwd)).map_err(std::io::Error::from)
-            })
-            .status()?
-    };
-    if let Some(c) = s.code() {
-        println!("command exited with code {}", c);
-    } else if !s.success() {
-        println!("command exited with unknown error");
+    Runner::new(pwd, program).args(args).timeout(timeout).run()
+}
+
+/// timeout of build and clean commands
+pub fn build_timeout() -> Option<Duration> {
+    process::timeouts().build
+}
+
+/// timeout of local test runs
+pub fn test_timeout() -> Option<Duration> {
+    process::timeouts().test
+}
+
+#[cfg(test)]
+mod test {
+    use codewars_types::KnownLangId;
+
+    use super::toolchain;
+
+    #[test]
+    fn substituted_toolchain() {
+        let (t, substituted) = toolchain(KnownLangId::Rust, "1.66");
+        assert_eq!((t.version_id, substituted), ("1.66", None));
+
+        let (t, substituted) = toolchain(KnownLangId::Rust, "1.79");
+        assert_eq!(t.version_id, "1.75");
+        assert_eq!(
+            substituted.unwrap().to_string(),
+            "unknown rust version 1.79, using toolchain of version 1.75"
+        );
     }
-    Ok(())
 }
@@ -30,10 +30,7 @@ pub fn mkdir_all_at(root: BorrowedFd, path: &Path) -> Result<(), std::io::Error>
                 mkdirat(root, path)?;
                 Ok(())
             }
-            None => Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "failed to create all dir",
-            )),
+            None => Err(std::io::Error::other("failed to create all dir")),
         },
         Err(Errno::EXIST) => Ok(()),
         Err(e) => Err(e.into()),
@@ -0,0 +1,384 @@
+use std::{
+    ffi::OsStr,
+    fmt::{self, Display},
+    io::{self, IsTerminal, Write},
+    os::unix::process::{CommandExt, ExitStatusExt},
+    process,
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+use rustix::{
+    event::{poll, PollFd, PollFlags},
+    fd::{AsRawFd, BorrowedFd, OwnedFd},
+    fs::{fcntl_getfl, fcntl_setfl, OFlags},
+    io::Errno,
+    process::{kill_process_group, Pid, Signal},
+};
+
+use crate::sandbox::{self, Sandbox};
+
+/// interval of polling child while waiting for output or exit
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitStatus {
+    Exited(i32),
+    Signaled(i32),
+    /// killed after running longer than timeout
+    TimedOut,
+}
+impl ExitStatus {
+    pub fn success(self) -> bool {
+        self == Self::Exited(0)
+    }
+}
+impl From<process::ExitStatus> for ExitStatus {
+    fn from(s: process::ExitStatus) -> Self {
+        match (s.code(), s.signal()) {
+            (Some(c), _) => Self::Exited(c),
+            (None, Some(s)) => Self::Signaled(s),
+            (None, None) => Self::Exited(-1),
+        }
+    }
+}
+impl Display for ExitStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exited(c) => write!(f, "exited with code {c}"),
+            Self::Signaled(s) => write!(f, "killed by signal {s}"),
+            Self::TimedOut => f.write_str("timed out"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Output {
+    pub program: String,
+    pub status: ExitStatus,
+    /// wall clock time until command exited or was killed
+    pub duration: Duration,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    /// output was captured without showing it to user
+    captured: bool,
+}
+impl Output {
+    /// Fail with [`CommandError`] unless command exited successfully
+    pub fn check(self) -> io::Result<Self> {
+        if self.status.success() {
+            Ok(self)
+        } else {
+            Err(io::Error::other(CommandError(self)))
+        }
+    }
+}
+
+/// Command failed to exit successfully
+#[derive(Debug)]
+pub struct CommandError(Output);
+impl CommandError {
+    pub fn output(&self) -> &Output {
+        &self.0
+    }
+}
+impl Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let output = &self.0;
+        write!(
+            f,
+            "`{}` {} after {:.1?}",
+            output.program, output.status, output.duration
+        )?;
+        if output.captured {
+            // last lines of stderr usually explain failure
+            const LINES: usize = 10;
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let lines: Vec<_> = stderr.trim_end().lines().collect();
+            for l in &lines[lines.len().saturating_sub(LINES)..] {
+                write!(f, "\n{l}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+/// Wall clock timeouts of workspace commands
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Timeouts {
+    /// builds and cleans
+    pub build: Option<Duration>,
+    /// local test runs
+    pub test: Option<Duration>,
+}
+
+static TIMEOUTS: OnceLock<Timeouts> = OnceLock::new();
+
+/// Set timeouts of all following workspace commands, fails if already set
+pub fn set_timeouts(timeouts: Timeouts) -> Result<(), Timeouts> {
+    TIMEOUTS.set(timeouts)
+}
+
+/// configured timeouts, none by default
+pub fn timeouts() -> Timeouts {
+    TIMEOUTS.get().copied().unwrap_or_default()
+}
+
+/// Run program in workspace directory, in sandbox if enabled
+pub struct Runner<'a> {
+    pwd: BorrowedFd<'a>,
+    program: String,
+    command: process::Command,
+    timeout: Option<Duration>,
+    interactive: bool,
+    capture: bool,
+    test: bool,
+}
+impl<'a> Runner<'a> {
+    pub fn new(pwd: BorrowedFd<'a>, program: impl AsRef<OsStr>) -> Self {
+        let interactive = io::stdout().is_terminal();
+        Self {
+            pwd,
+            program: program.as_ref().to_string_lossy().into_owned(),
+            command: process::Command::new(program),
+            timeout: None,
+            interactive,
+            capture: !interactive,
+            test: false,
+        }
+    }
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.command.args(args);
+        self
+    }
+    /// kill command after `timeout`, with its whole process group unless interactive
+    pub fn timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+    /// whether user watches output, default to whether stdout is terminal
+    ///
+    /// Interactive commands inherit stdin and stay in the foreground process group, so they can
+    /// read the terminal and receive Ctrl-C. Captured output is streamed live.
+    pub fn interactive(mut self, interactive: bool) -> Self {
+        self.interactive = interactive;
+        self
+    }
+    /// collect stdout and stderr instead of inheriting them, default to not interactive
+    pub fn capture(mut self, capture: bool) -> Self {
+        self.capture = capture;
+        self
+    }
+
+    /// mark command as test run, limited like on Codewars in sandbox
+    pub fn test(mut self, test: bool) -> Self {
+        self.test = test;
+        self
+    }
+
+    pub fn run(self) -> io::Result<Output> {
+        self.run_in(sandbox::current())
+    }
+
+    pub(crate) fn run_in(mut self, sandbox: Option<&Sandbox>) -> io::Result<Output> {
+        let pwd = self.pwd.as_raw_fd();
+        if let Some(t) = sandbox
+            .filter(|_| self.test)
+            .and_then(Sandbox::test_timeout)
+        {
+            self.timeout = Some(self.timeout.map_or(t, |s| s.min(t)));
+        }
+        let mut sandbox = sandbox
+            .map(|s| s.prepare(self.pwd, self.test))
+            .transpose()?;
+        unsafe {
+            self.command.pre_exec(move || {
+                rustix::process::fchdir(BorrowedFd::borrow_raw(pwd))
+                    .map_err(std::io::Error::from)?;
+                match &mut sandbox {
+                    Some(s) => s.apply(),
+                    None => Ok(()),
+                }
+            });
+        }
+        let stdio = || {
+            if self.capture {
+                process::Stdio::piped()
+            } else {
+                process::Stdio::inherit()
+            }
+        };
+        if self.interactive {
+            self.command.stdin(process::Stdio::inherit());
+        } else {
+            // own group lets timeout kill background processes started by command
+            self.command.stdin(process::Stdio::null()).process_group(0);
+        }
+        let mut child = self.command.stdout(stdio()).stderr(stdio()).spawn()?;
+        let start = Instant::now();
+
+        let echo = self.capture && self.interactive;
+        let mut pipes = [
+            Pipe::new(
+                child.stdout.take(),
+                echo.then(|| Box::new(io::stdout()) as _),
+            )?,
+            Pipe::new(
+                child.stderr.take(),
+                echo.then(|| Box::new(io::stderr()) as _),
+            )?,
+        ];
+        let status = loop {
+            for p in &mut pipes {
+                p.drain()?;
+            }
+            if let Some(s) = child.try_wait()? {
+                break s.into();
+            }
+            if self.timeout.is_some_and(|t| start.elapsed() >= t) {
+                if self.interactive {
+                    // group is shared with caller
+                    let _ = child.kill();
+                } else {
+                    // leader is not reaped yet, so its pid still names the group
+                    let _ = kill_process_group(Pid::from_child(&child), Signal::Kill);
+                }
+                child.wait()?;
+                break ExitStatus::TimedOut;
+            }
+            let mut fds: Vec<_> = pipes
+                .iter()
+                .filter_map(|p| p.fd.as_ref())
+                .map(|fd| PollFd::new(fd, PollFlags::IN))
+                .collect();
+            poll(&mut fds, POLL_INTERVAL.as_millis() as i32)?;
+        };
+        let duration = start.elapsed();
+        // background processes may keep pipes open, take only output written so far
+        for p in &mut pipes {
+            p.drain()?;
+        }
+        let [stdout, stderr] = pipes.map(|p| p.data);
+
+        Ok(Output {
+            program: self.program,
+            status,
+            duration,
+            stdout,
+            stderr,
+            captured: self.capture && !self.interactive,
+        })
+    }
+}
+
+/// Captured output pipe read without blocking
+struct Pipe {
+    /// `None` when not captured or closed
+    fd: Option<OwnedFd>,
+    echo: Option<Box<dyn Write>>,
+    data: Vec<u8>,
+}
+impl Pipe {
+    fn new(fd: Option<impl Into<OwnedFd>>, echo: Option<Box<dyn Write>>) -> io::Result<Self> {
+        let fd = fd.map(Into::into);
+        if let Some(fd) = &fd {
+            fcntl_setfl(fd, fcntl_getfl(fd)? | OFlags::NONBLOCK)?;
+        }
+        Ok(Self {
+            fd,
+            echo,
+            data: Vec::new(),
+        })
+    }
+    /// read everything available now, copying it to `echo`
+    fn drain(&mut self) -> io::Result<()> {
+        let Some(fd) = &self.fd else {
+            return Ok(());
+        };
+        let mut buf = [0; 4096];
+        loop {
+            let n = match rustix::io::read(fd, &mut buf) {
+                Ok(0) => {
+                    self.fd = None;
+                    return Ok(());
+                }
+                Ok(n) => n,
+                Err(Errno::INTR) => continue,
+                Err(Errno::AGAIN) => return Ok(()),
+                Err(e) => return Err(e.into()),
+            };
+            if let Some(w) = &mut self.echo {
+                w.write_all(&buf[..n])?;
+                w.flush()?;
+            }
+            self.data.extend_from_slice(&buf[..n]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use rustix::{
+        fd::AsFd,
+        fs::{open, Mode, OFlags},
+        process::{kill_process, test_kill_process, Pid, Signal},
+    };
+
+    use super::{ExitStatus, Runner};
+
+    fn sh(script: &str, timeout: Option<Duration>) -> super::Output {
+        let dir = open("/tmp", OFlags::DIRECTORY, Mode::empty()).unwrap();
+        Runner::new(dir.as_fd(), "sh")
+            .args(["-c", script])
+            .timeout(timeout)
+            .interactive(false)
+            .capture(true)
+            .run()
+            .unwrap()
+    }
+
+    #[test]
+    fn capture() {
+        let out = sh("pwd; echo err >&2; exit 3", None);
+        assert_eq!(out.status, ExitStatus::Exited(3));
+        assert_eq!(out.stdout, b"/tmp\n");
+        assert_eq!(out.stderr, b"err\n");
+    }
+
+    #[test]
+    fn timeout() {
+        // background process holding output pipe open is killed with its group
+        let out = sh("sleep 10 & sleep 10", Some(Duration::from_millis(100)));
+        assert_eq!(out.status, ExitStatus::TimedOut);
+        assert!(out.duration < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn background_process() {
+        // leftover process keeps pipe open, but neither blocks nor gets killed
+        let out = sh("sleep 5 & echo $!", None);
+        assert!(out.duration < Duration::from_secs(2));
+        let pid = String::from_utf8(out.stdout).unwrap();
+        let pid = Pid::from_raw(pid.trim().parse().unwrap()).unwrap();
+        test_kill_process(pid).expect("background process is still running");
+        kill_process(pid, Signal::Kill).unwrap();
+    }
+
+    #[test]
+    fn failure() {
+        let out = sh("echo oops >&2; exit 1", None);
+        assert_eq!(out.program, "sh");
+        let err = out.check().unwrap_err();
+        let err = err.get_ref().unwrap().to_string();
+        assert!(err.starts_with("`sh` exited with code 1 after"));
+        assert!(err.ends_with("\noops"));
+    }
+}